@@ -0,0 +1,637 @@
+#[allow(non_snake_case)]
+pub mod types {
+    fn with_attributes<'a>(
+        start: suds_util::xml::events::BytesStart<'a>,
+    ) -> suds_util::xml::events::BytesStart<'a> {
+        start
+            .with_attributes([("xmlns:ns0", "http://example.com/ping")])
+            .with_attributes([("xmlns:ns1", "http://www.w3.org/2001/XMLSchema")])
+    }
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct InvalidPing {
+        pub reason: String,
+    }
+    impl suds_util::xml::ToXml for InvalidPing {
+        fn to_xml<W: std::io::Write>(
+            &self,
+            writer: &mut suds_util::xml::Writer<W>,
+            top_level: bool,
+        ) {
+            suds_util::xml::ToXml::to_xml_named(
+                self,
+                writer,
+                "ns0:InvalidPing",
+                top_level,
+            )
+        }
+        fn to_xml_named<W: std::io::Write>(
+            &self,
+            writer: &mut suds_util::xml::Writer<W>,
+            name: &str,
+            mut top_level: bool,
+        ) {
+            let start = suds_util::xml::events::BytesStart::owned_name(name);
+            let start = if top_level { with_attributes(start) } else { start };
+            top_level = false;
+            writer
+                .write_event(suds_util::xml::events::Event::Start(start.to_borrowed()))
+                .unwrap();
+            {
+                let start = suds_util::xml::events::BytesStart::owned_name("ns0:reason");
+                let string = &self.reason.to_string();
+                let value = suds_util::xml::events::BytesText::from_plain_str(&string);
+                writer
+                    .write_event(
+                        suds_util::xml::events::Event::Start(start.to_borrowed()),
+                    )
+                    .unwrap();
+                writer.write_event(suds_util::xml::events::Event::Text(value)).unwrap();
+                writer
+                    .write_event(suds_util::xml::events::Event::End(start.to_end()))
+                    .unwrap();
+            }
+            writer
+                .write_event(suds_util::xml::events::Event::End(start.to_end()))
+                .unwrap();
+        }
+    }
+    impl suds_util::xml::FromXml for InvalidPing {
+        fn from_xml<R: std::io::BufRead>(
+            reader: &mut suds_util::xml::Reader<R>,
+            buffer: &mut Vec<u8>,
+        ) -> Result<Self, suds_util::error::XmlError> {
+            <Self as suds_util::xml::FromXml>::from_xml_named(
+                reader,
+                buffer,
+                "http://example.com/ping",
+                "InvalidPing",
+            )
+        }
+        fn from_xml_named<R: std::io::BufRead>(
+            reader: &mut suds_util::xml::Reader<R>,
+            buffer: &mut Vec<u8>,
+            namespace: &str,
+            name: &str,
+        ) -> Result<Self, suds_util::error::XmlError> {
+            suds_util::xml::expect_start_ns(reader, buffer, namespace, name)?;
+            let reason = {
+                suds_util::xml::expect_start_ns(
+                    reader,
+                    buffer,
+                    "http://example.com/ping",
+                    "reason",
+                )?;
+                {
+                    let value = suds_util::xml::expect_value(reader, buffer)?;
+                    suds_util::xml::expect_end(reader, buffer)?;
+                    value
+                }
+            };
+            suds_util::xml::expect_end(reader, buffer)?;
+            Ok(Self { reason })
+        }
+    }
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct Ping {
+        pub value: String,
+    }
+    impl suds_util::xml::ToXml for Ping {
+        fn to_xml<W: std::io::Write>(
+            &self,
+            writer: &mut suds_util::xml::Writer<W>,
+            top_level: bool,
+        ) {
+            suds_util::xml::ToXml::to_xml_named(self, writer, "ns0:Ping", top_level)
+        }
+        fn to_xml_named<W: std::io::Write>(
+            &self,
+            writer: &mut suds_util::xml::Writer<W>,
+            name: &str,
+            mut top_level: bool,
+        ) {
+            let start = suds_util::xml::events::BytesStart::owned_name(name);
+            let start = if top_level { with_attributes(start) } else { start };
+            top_level = false;
+            writer
+                .write_event(suds_util::xml::events::Event::Start(start.to_borrowed()))
+                .unwrap();
+            {
+                let start = suds_util::xml::events::BytesStart::owned_name("ns0:value");
+                let string = &self.value.to_string();
+                let value = suds_util::xml::events::BytesText::from_plain_str(&string);
+                writer
+                    .write_event(
+                        suds_util::xml::events::Event::Start(start.to_borrowed()),
+                    )
+                    .unwrap();
+                writer.write_event(suds_util::xml::events::Event::Text(value)).unwrap();
+                writer
+                    .write_event(suds_util::xml::events::Event::End(start.to_end()))
+                    .unwrap();
+            }
+            writer
+                .write_event(suds_util::xml::events::Event::End(start.to_end()))
+                .unwrap();
+        }
+    }
+    impl suds_util::xml::FromXml for Ping {
+        fn from_xml<R: std::io::BufRead>(
+            reader: &mut suds_util::xml::Reader<R>,
+            buffer: &mut Vec<u8>,
+        ) -> Result<Self, suds_util::error::XmlError> {
+            <Self as suds_util::xml::FromXml>::from_xml_named(
+                reader,
+                buffer,
+                "http://example.com/ping",
+                "Ping",
+            )
+        }
+        fn from_xml_named<R: std::io::BufRead>(
+            reader: &mut suds_util::xml::Reader<R>,
+            buffer: &mut Vec<u8>,
+            namespace: &str,
+            name: &str,
+        ) -> Result<Self, suds_util::error::XmlError> {
+            suds_util::xml::expect_start_ns(reader, buffer, namespace, name)?;
+            let value = {
+                suds_util::xml::expect_start_ns(
+                    reader,
+                    buffer,
+                    "http://example.com/ping",
+                    "value",
+                )?;
+                {
+                    let value = suds_util::xml::expect_value(reader, buffer)?;
+                    suds_util::xml::expect_end(reader, buffer)?;
+                    value
+                }
+            };
+            suds_util::xml::expect_end(reader, buffer)?;
+            Ok(Self { value })
+        }
+    }
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct PingResponse {
+        pub result: String,
+    }
+    impl suds_util::xml::ToXml for PingResponse {
+        fn to_xml<W: std::io::Write>(
+            &self,
+            writer: &mut suds_util::xml::Writer<W>,
+            top_level: bool,
+        ) {
+            suds_util::xml::ToXml::to_xml_named(
+                self,
+                writer,
+                "ns0:PingResponse",
+                top_level,
+            )
+        }
+        fn to_xml_named<W: std::io::Write>(
+            &self,
+            writer: &mut suds_util::xml::Writer<W>,
+            name: &str,
+            mut top_level: bool,
+        ) {
+            let start = suds_util::xml::events::BytesStart::owned_name(name);
+            let start = if top_level { with_attributes(start) } else { start };
+            top_level = false;
+            writer
+                .write_event(suds_util::xml::events::Event::Start(start.to_borrowed()))
+                .unwrap();
+            {
+                let start = suds_util::xml::events::BytesStart::owned_name("ns0:result");
+                let string = &self.result.to_string();
+                let value = suds_util::xml::events::BytesText::from_plain_str(&string);
+                writer
+                    .write_event(
+                        suds_util::xml::events::Event::Start(start.to_borrowed()),
+                    )
+                    .unwrap();
+                writer.write_event(suds_util::xml::events::Event::Text(value)).unwrap();
+                writer
+                    .write_event(suds_util::xml::events::Event::End(start.to_end()))
+                    .unwrap();
+            }
+            writer
+                .write_event(suds_util::xml::events::Event::End(start.to_end()))
+                .unwrap();
+        }
+    }
+    impl suds_util::xml::FromXml for PingResponse {
+        fn from_xml<R: std::io::BufRead>(
+            reader: &mut suds_util::xml::Reader<R>,
+            buffer: &mut Vec<u8>,
+        ) -> Result<Self, suds_util::error::XmlError> {
+            <Self as suds_util::xml::FromXml>::from_xml_named(
+                reader,
+                buffer,
+                "http://example.com/ping",
+                "PingResponse",
+            )
+        }
+        fn from_xml_named<R: std::io::BufRead>(
+            reader: &mut suds_util::xml::Reader<R>,
+            buffer: &mut Vec<u8>,
+            namespace: &str,
+            name: &str,
+        ) -> Result<Self, suds_util::error::XmlError> {
+            suds_util::xml::expect_start_ns(reader, buffer, namespace, name)?;
+            let result = {
+                suds_util::xml::expect_start_ns(
+                    reader,
+                    buffer,
+                    "http://example.com/ping",
+                    "result",
+                )?;
+                {
+                    let value = suds_util::xml::expect_value(reader, buffer)?;
+                    suds_util::xml::expect_end(reader, buffer)?;
+                    value
+                }
+            };
+            suds_util::xml::expect_end(reader, buffer)?;
+            Ok(Self { result })
+        }
+    }
+}
+#[allow(non_snake_case)]
+pub mod messages {
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct InvalidPing {
+        pub fault: super::types::InvalidPing,
+    }
+    impl suds_util::xml::ToXml for InvalidPing {
+        fn to_xml<W: std::io::Write>(
+            &self,
+            writer: &mut suds_util::xml::Writer<W>,
+            top_level: bool,
+        ) {
+            suds_util::xml::ToXml::to_xml(&self.fault, writer, top_level);
+        }
+    }
+    impl suds_util::xml::FromXml for InvalidPing {
+        fn from_xml<R: std::io::BufRead>(
+            reader: &mut suds_util::xml::Reader<R>,
+            buffer: &mut Vec<u8>,
+        ) -> Result<Self, suds_util::error::XmlError> {
+            let fault = <super::types::InvalidPing as suds_util::xml::FromXml>::from_xml(
+                reader,
+                buffer,
+            )?;
+            Ok(Self { fault })
+        }
+    }
+    impl From<super::types::InvalidPing> for InvalidPing {
+        fn from(value: super::types::InvalidPing) -> Self {
+            Self { fault: value }
+        }
+    }
+    impl InvalidPing {
+        pub fn builder() -> InvalidPingBuilder {
+            InvalidPingBuilder::default()
+        }
+    }
+    #[derive(Default)]
+    pub struct InvalidPingBuilder {
+        fault: Option<super::types::InvalidPing>,
+    }
+    impl InvalidPingBuilder {
+        pub fn fault(mut self, value: super::types::InvalidPing) -> Self {
+            self.fault = Some(value);
+            self
+        }
+        pub fn build(self) -> Result<InvalidPing, suds_util::error::BuildError> {
+            Ok(InvalidPing {
+                fault: self
+                    .fault
+                    .ok_or(suds_util::error::BuildError::MissingField("fault"))?,
+            })
+        }
+    }
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct PingRequest {
+        pub parameters: super::types::Ping,
+    }
+    impl suds_util::xml::ToXml for PingRequest {
+        fn to_xml<W: std::io::Write>(
+            &self,
+            writer: &mut suds_util::xml::Writer<W>,
+            top_level: bool,
+        ) {
+            suds_util::xml::ToXml::to_xml(&self.parameters, writer, top_level);
+        }
+    }
+    impl suds_util::xml::FromXml for PingRequest {
+        fn from_xml<R: std::io::BufRead>(
+            reader: &mut suds_util::xml::Reader<R>,
+            buffer: &mut Vec<u8>,
+        ) -> Result<Self, suds_util::error::XmlError> {
+            let parameters = <super::types::Ping as suds_util::xml::FromXml>::from_xml(
+                reader,
+                buffer,
+            )?;
+            Ok(Self { parameters })
+        }
+    }
+    impl From<super::types::Ping> for PingRequest {
+        fn from(value: super::types::Ping) -> Self {
+            Self { parameters: value }
+        }
+    }
+    impl PingRequest {
+        pub fn builder() -> PingRequestBuilder {
+            PingRequestBuilder::default()
+        }
+    }
+    #[derive(Default)]
+    pub struct PingRequestBuilder {
+        parameters: Option<super::types::Ping>,
+    }
+    impl PingRequestBuilder {
+        pub fn parameters(mut self, value: super::types::Ping) -> Self {
+            self.parameters = Some(value);
+            self
+        }
+        pub fn build(self) -> Result<PingRequest, suds_util::error::BuildError> {
+            Ok(PingRequest {
+                parameters: self
+                    .parameters
+                    .ok_or(suds_util::error::BuildError::MissingField("parameters"))?,
+            })
+        }
+    }
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct PingResponse {
+        pub parameters: super::types::PingResponse,
+    }
+    impl suds_util::xml::ToXml for PingResponse {
+        fn to_xml<W: std::io::Write>(
+            &self,
+            writer: &mut suds_util::xml::Writer<W>,
+            top_level: bool,
+        ) {
+            suds_util::xml::ToXml::to_xml(&self.parameters, writer, top_level);
+        }
+    }
+    impl suds_util::xml::FromXml for PingResponse {
+        fn from_xml<R: std::io::BufRead>(
+            reader: &mut suds_util::xml::Reader<R>,
+            buffer: &mut Vec<u8>,
+        ) -> Result<Self, suds_util::error::XmlError> {
+            let parameters = <super::types::PingResponse as suds_util::xml::FromXml>::from_xml(
+                reader,
+                buffer,
+            )?;
+            Ok(Self { parameters })
+        }
+    }
+    impl From<super::types::PingResponse> for PingResponse {
+        fn from(value: super::types::PingResponse) -> Self {
+            Self { parameters: value }
+        }
+    }
+    impl PingResponse {
+        pub fn builder() -> PingResponseBuilder {
+            PingResponseBuilder::default()
+        }
+    }
+    #[derive(Default)]
+    pub struct PingResponseBuilder {
+        parameters: Option<super::types::PingResponse>,
+    }
+    impl PingResponseBuilder {
+        pub fn parameters(mut self, value: super::types::PingResponse) -> Self {
+            self.parameters = Some(value);
+            self
+        }
+        pub fn build(self) -> Result<PingResponse, suds_util::error::BuildError> {
+            Ok(PingResponse {
+                parameters: self
+                    .parameters
+                    .ok_or(suds_util::error::BuildError::MissingField("parameters"))?,
+            })
+        }
+    }
+}
+#[allow(non_snake_case)]
+pub mod services {
+    pub trait PingPortType {
+        fn Ping(
+            &self,
+            input: super::messages::PingRequest,
+        ) -> Result<super::messages::PingResponse, super::services::PingError>;
+    }
+    #[derive(Debug)]
+    pub enum PingError {
+        InvalidPing(super::messages::InvalidPing),
+        Fault(suds_util::error::SoapError),
+    }
+    impl From<suds_util::error::SoapError> for PingError {
+        fn from(error: suds_util::error::SoapError) -> Self {
+            if let suds_util::error::SoapError::Fault(fault) = &error {
+                if let Some(detail) = fault.detail_as::<super::messages::InvalidPing>() {
+                    return Self::InvalidPing(detail);
+                }
+            }
+            Self::Fault(error)
+        }
+    }
+    impl std::fmt::Display for PingError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::InvalidPing(_) => write!(f, "{} fault", "InvalidPing"),
+                Self::Fault(error) => write!(f, "{}", error),
+            }
+        }
+    }
+    impl std::error::Error for PingError {}
+    pub mod PingService {
+        pub struct PingPort {
+            client: suds_util::soap::Client,
+        }
+        impl PingPort {
+            pub const ENDPOINT: &str = "http://example.com/ping";
+            pub const PING_SOAP_ACTION: &str = "http://example.com/ping/Ping";
+            pub fn new() -> Self {
+                Self {
+                    client: suds_util::soap::Client::new("http://example.com/ping")
+                        .with_version(suds_util::soap::SoapVersion::Soap11),
+                }
+            }
+            pub fn with_location(location: &str) -> Self {
+                Self {
+                    client: suds_util::soap::Client::new(location)
+                        .with_version(suds_util::soap::SoapVersion::Soap11),
+                }
+            }
+            pub fn with_client(client: suds_util::soap::Client) -> Self {
+                Self {
+                    client: client
+                        .with_url("http://example.com/ping")
+                        .with_version(suds_util::soap::SoapVersion::Soap11),
+                }
+            }
+            pub fn with_reqwest(client: suds_util::soap::Reqwest) -> Self {
+                Self {
+                    client: suds_util::soap::Client::from_reqwest(
+                            "http://example.com/ping",
+                            client,
+                        )
+                        .with_version(suds_util::soap::SoapVersion::Soap11),
+                }
+            }
+            pub fn with_basic_auth(username: &str, password: &str) -> Self {
+                Self {
+                    client: suds_util::soap::Client::with_basic_auth(
+                            "http://example.com/ping",
+                            username,
+                            password,
+                        )
+                        .with_version(suds_util::soap::SoapVersion::Soap11),
+                }
+            }
+            pub fn with_timeout(
+                timeout: std::time::Duration,
+            ) -> Result<Self, suds_util::error::SoapError> {
+                Ok(Self {
+                    client: suds_util::soap::Client::with_timeout(
+                            "http://example.com/ping",
+                            timeout,
+                        )?
+                        .with_version(suds_util::soap::SoapVersion::Soap11),
+                })
+            }
+            pub fn with_proxy(
+                proxy: suds_util::soap::Proxy,
+            ) -> Result<Self, suds_util::error::SoapError> {
+                Ok(Self {
+                    client: suds_util::soap::Client::with_proxy(
+                            "http://example.com/ping",
+                            proxy,
+                        )?
+                        .with_version(suds_util::soap::SoapVersion::Soap11),
+                })
+            }
+            pub fn with_redirect_policy(
+                policy: suds_util::soap::RedirectPolicy,
+            ) -> Result<Self, suds_util::error::SoapError> {
+                Ok(Self {
+                    client: suds_util::soap::Client::with_redirect_policy(
+                            "http://example.com/ping",
+                            policy,
+                        )?
+                        .with_version(suds_util::soap::SoapVersion::Soap11),
+                })
+            }
+            pub fn with_cookies() -> Result<Self, suds_util::error::SoapError> {
+                Ok(Self {
+                    client: suds_util::soap::Client::with_cookies(
+                            "http://example.com/ping",
+                        )?
+                        .with_version(suds_util::soap::SoapVersion::Soap11),
+                })
+            }
+            pub fn danger_accept_invalid_certs(
+                accept: bool,
+            ) -> Result<Self, suds_util::error::SoapError> {
+                Ok(Self {
+                    client: suds_util::soap::Client::danger_accept_invalid_certs(
+                            "http://example.com/ping",
+                            accept,
+                        )?
+                        .with_version(suds_util::soap::SoapVersion::Soap11),
+                })
+            }
+            pub fn with_security(
+                mut self,
+                security: suds_util::security::SecurityHeader,
+            ) -> Self {
+                self.client = self.client.with_security(security);
+                self
+            }
+            pub fn with_validation(mut self, validate: bool) -> Self {
+                self.client = self.client.with_validation(validate);
+                self
+            }
+            pub fn with_header<K: suds_util::soap::IntoHeaderName>(
+                mut self,
+                name: K,
+                value: suds_util::soap::HeaderValue,
+            ) -> Self {
+                self.client = self.client.with_header(name, value);
+                self
+            }
+            pub fn with_headers(mut self, headers: suds_util::soap::HeaderMap) -> Self {
+                self.client = self.client.with_headers(headers);
+                self
+            }
+            pub fn Ping(
+                &self,
+                input: impl Into<super::super::messages::PingRequest>,
+            ) -> Result<
+                super::super::messages::PingResponse,
+                super::super::services::PingError,
+            > {
+                let envelope = suds_util::soap::Envelope::new(input.into());
+                Ok(self.client.send(Self::PING_SOAP_ACTION, envelope)?.into_body())
+            }
+            pub fn Ping_with_headers(
+                &self,
+                input: impl Into<super::super::messages::PingRequest>,
+                headers: suds_util::soap::HeaderMap,
+            ) -> Result<
+                super::super::messages::PingResponse,
+                super::super::services::PingError,
+            > {
+                let envelope = suds_util::soap::Envelope::new(input.into());
+                Ok(
+                    self
+                        .client
+                        .send_with_headers(Self::PING_SOAP_ACTION, envelope, headers)?
+                        .into_body(),
+                )
+            }
+            pub fn Ping_raw(
+                &self,
+                input: impl Into<super::super::messages::PingRequest>,
+            ) -> Result<String, suds_util::error::SoapError> {
+                let envelope = suds_util::soap::Envelope::new(input.into());
+                self.client.send_raw(Self::PING_SOAP_ACTION, envelope)
+            }
+        }
+        impl super::PingPortType for PingPort {
+            fn Ping(
+                &self,
+                input: super::super::messages::PingRequest,
+            ) -> Result<
+                super::super::messages::PingResponse,
+                super::super::services::PingError,
+            > {
+                Self::Ping(self, input)
+            }
+        }
+    }
+    pub struct ServiceRegistry {
+        pub ping_service_ping_port: PingService::PingPort,
+    }
+    impl Default for ServiceRegistry {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+    impl ServiceRegistry {
+        pub fn new() -> Self {
+            Self {
+                ping_service_ping_port: PingService::PingPort::new(),
+            }
+        }
+        pub fn with_client(client: suds_util::soap::Client) -> Self {
+            Self {
+                ping_service_ping_port: PingService::PingPort::with_client(
+                    client.clone(),
+                ),
+            }
+        }
+    }
+}
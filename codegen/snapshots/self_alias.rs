@@ -0,0 +1,28 @@
+#[allow(non_snake_case)]
+pub mod types {
+    fn with_attributes<'a>(
+        start: suds_util::xml::events::BytesStart<'a>,
+    ) -> suds_util::xml::events::BytesStart<'a> {
+        start.with_attributes([("xmlns:ns0", "http://example.com/test")])
+    }
+    compile_error!("Type Loop is declared as an alias of itself");
+}
+#[allow(non_snake_case)]
+pub mod messages {}
+#[allow(non_snake_case)]
+pub mod services {
+    pub struct ServiceRegistry {}
+    impl Default for ServiceRegistry {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+    impl ServiceRegistry {
+        pub fn new() -> Self {
+            Self {}
+        }
+        pub fn with_client(client: suds_util::soap::Client) -> Self {
+            Self {}
+        }
+    }
+}
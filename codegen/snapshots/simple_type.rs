@@ -0,0 +1,110 @@
+#[allow(non_snake_case)]
+pub mod types {
+    fn with_attributes<'a>(
+        start: suds_util::xml::events::BytesStart<'a>,
+    ) -> suds_util::xml::events::BytesStart<'a> {
+        start
+            .with_attributes([("xmlns:ns0", "http://example.com/test")])
+            .with_attributes([("xmlns:ns1", "http://www.w3.org/2001/XMLSchema")])
+    }
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct Code(pub String);
+    impl suds_util::xml::ToXml for Code {
+        fn to_xml<W: std::io::Write>(
+            &self,
+            writer: &mut suds_util::xml::Writer<W>,
+            top_level: bool,
+        ) {
+            suds_util::xml::ToXml::to_xml_named(self, writer, "ns0:Code", top_level)
+        }
+        fn to_xml_named<W: std::io::Write>(
+            &self,
+            writer: &mut suds_util::xml::Writer<W>,
+            name: &str,
+            mut top_level: bool,
+        ) {
+            let start = suds_util::xml::events::BytesStart::owned_name(name);
+            let start = if top_level { with_attributes(start) } else { start };
+            top_level = false;
+            let string = self.0.to_string();
+            let value = suds_util::xml::events::BytesText::from_plain_str(&string);
+            writer
+                .write_event(suds_util::xml::events::Event::Start(start.to_borrowed()))
+                .unwrap();
+            writer.write_event(suds_util::xml::events::Event::Text(value)).unwrap();
+            writer
+                .write_event(suds_util::xml::events::Event::End(start.to_end()))
+                .unwrap();
+        }
+    }
+    impl suds_util::xml::FromXml for Code {
+        fn from_xml<R: std::io::BufRead>(
+            reader: &mut suds_util::xml::Reader<R>,
+            buffer: &mut Vec<u8>,
+        ) -> Result<Self, suds_util::error::XmlError> {
+            <Self as suds_util::xml::FromXml>::from_xml_named(
+                reader,
+                buffer,
+                "http://example.com/test",
+                "Code",
+            )
+        }
+        fn from_xml_named<R: std::io::BufRead>(
+            reader: &mut suds_util::xml::Reader<R>,
+            buffer: &mut Vec<u8>,
+            namespace: &str,
+            name: &str,
+        ) -> Result<Self, suds_util::error::XmlError> {
+            suds_util::xml::expect_start_ns(reader, buffer, namespace, name)?;
+            let value = Self({
+                let value = suds_util::xml::expect_value(reader, buffer)?;
+                suds_util::xml::expect_end(reader, buffer)?;
+                value
+            });
+            if reader.validates() {
+                suds_util::xml::Validate::validate(&value)
+                    .map_err(|source| suds_util::error::XmlError::Validation {
+                        source,
+                        position: reader.position(),
+                    })?;
+            }
+            Ok(value)
+        }
+    }
+    impl suds_util::xml::Validate for Code {
+        fn validate(&self) -> Result<(), suds_util::error::ValidationError> {
+            if self.0.to_string().chars().count() > 3usize {
+                return Err(
+                    suds_util::error::ValidationError::Length(
+                        self.0.to_string().chars().count(),
+                    ),
+                );
+            }
+            Ok(())
+        }
+    }
+    impl std::fmt::Display for Code {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(&self.0.to_string())
+        }
+    }
+}
+#[allow(non_snake_case)]
+pub mod messages {}
+#[allow(non_snake_case)]
+pub mod services {
+    pub struct ServiceRegistry {}
+    impl Default for ServiceRegistry {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+    impl ServiceRegistry {
+        pub fn new() -> Self {
+            Self {}
+        }
+        pub fn with_client(client: suds_util::soap::Client) -> Self {
+            Self {}
+        }
+    }
+}
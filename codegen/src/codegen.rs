@@ -1,6 +1,6 @@
 use super::types;
 use std::collections::{HashMap, HashSet, hash_map::Entry};
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Ident, Literal, TokenStream};
 use quote::{format_ident, quote};
 use suds_wsdl::types::{self as wsdl, NamespacedName, Namespaces};
 
@@ -8,7 +8,12 @@ use suds_wsdl::types::{self as wsdl, NamespacedName, Namespaces};
 pub struct State {
     added_types: HashSet<NamespacedName>,
     rust_names: HashMap<NamespacedName, Ident>,
-    name_counts: HashMap<String, u64>,
+    colliding_names: HashSet<NamespacedName>,
+    default_types: HashSet<NamespacedName>,
+    declared_types: HashSet<NamespacedName>,
+    substitution_heads: HashSet<NamespacedName>,
+    added_errors: HashSet<NamespacedName>,
+    namespaces: Vec<String>,
 }
 
 pub trait Codegen {
@@ -24,32 +29,389 @@ impl State {
         self.added_types.insert(name)
     }
 
+    pub fn is_new_error(&mut self, operation: &NamespacedName) -> bool {
+        self.added_errors.insert(operation.clone())
+    }
+
+    pub fn add_default_types(&mut self, types: &[wsdl::Type]) {
+        loop {
+            let count = self.default_types.len();
+
+            for ty in types {
+                // Substitution group heads are replaced by an enum of their members, which has no default
+                if self.substitution_heads.contains(&ty.name) {
+                    continue;
+                }
+
+                if type_has_default(&ty.kind, &self.default_types) {
+                    self.default_types.insert(ty.name.clone());
+                }
+            }
+
+            if count == self.default_types.len() {
+                break;
+            }
+        }
+    }
+
+    pub fn add_declared_types(&mut self, types: &[wsdl::Type]) {
+        for ty in types {
+            match &ty.kind {
+                wsdl::TypeKind::Alias(alias) if *alias == ty.name => (),
+                _ => {
+                    self.declared_types.insert(ty.name.clone());
+                }
+            }
+        }
+    }
+
+    pub fn namespace(&self, name: &NamespacedName) -> String {
+        self.namespaces.get(name.index()).cloned().unwrap_or_default()
+    }
+
+    pub fn xml_name(&self, name: &NamespacedName) -> String {
+        if self.namespace(name).is_empty() {
+            name.name.clone()
+        } else {
+            format!("ns{}:{}", self.namespace_index(name), &name.name)
+        }
+    }
+
+    fn sorted<T: Clone>(&self, items: &[T], name: impl Fn(&T) -> &NamespacedName) -> Vec<T> {
+        let mut items = items.to_vec();
+        items.sort_by_cached_key(|item| (self.namespace(name(item)), name(item).name.clone()));
+        items
+    }
+
+    fn add_colliding_names<'a>(&mut self, names: impl Iterator<Item = &'a NamespacedName>) {
+        let mut by_name = HashMap::<&str, Vec<&NamespacedName>>::new();
+
+        for name in names {
+            let entry = by_name.entry(&name.name).or_default();
+
+            if !entry.contains(&name) {
+                entry.push(name);
+            }
+        }
+
+        for names in by_name.into_values().filter(|names| names.len() > 1) {
+            self.colliding_names.extend(names.into_iter().cloned());
+        }
+    }
+
+    pub fn add_names(&mut self, definition: &types::Definition) {
+        // Only names that end up in the same generated module can collide
+        self.add_colliding_names(definition.types.iter().map(|ty| &ty.name));
+        self.add_colliding_names(definition.messages.iter().map(|message| &message.name));
+        self.add_colliding_names(
+            definition
+                .port_types
+                .iter()
+                .map(|port_type| &port_type.name)
+                .chain(definition.services.iter().map(|service| &service.name)),
+        );
+
+        for service in &definition.services {
+            self.add_colliding_names(service.ports.iter().map(|port| &port.name));
+
+            for port in &service.ports {
+                self.add_colliding_names(port.operations.iter().map(|operation| &operation.name));
+            }
+        }
+    }
+
+    fn namespace_index(&self, name: &NamespacedName) -> usize {
+        // Indices follow the sorted namespace URIs rather than the order they were parsed in
+        let namespace = self.namespace(name);
+        self.namespaces.iter().filter(|other| **other < namespace).count()
+    }
+
+    pub fn add_substitution_groups(&mut self, groups: &[wsdl::SubstitutionGroup]) {
+        self.substitution_heads
+            .extend(groups.iter().map(|group| group.head.clone()));
+    }
+
+    pub fn group_name(&mut self, head: &NamespacedName) -> Ident {
+        format_ident!("{}Group", self.rust_name(head))
+    }
+
+    pub fn error_name(&mut self, operation: &NamespacedName) -> Ident {
+        format_ident!("{}Error", self.rust_name(operation))
+    }
+
     pub fn rust_name(&mut self, name: &NamespacedName) -> Ident {
-        match self.rust_names.entry(name.clone()) {
-            Entry::Occupied(name_entry) => name_entry.get().clone(),
-            Entry::Vacant(name_entry) => match self.name_counts.entry(name.name.to_string()) {
-                Entry::Occupied(mut count_entry) => {
-                    let value = count_entry.get_mut();
-                    *value += 1;
-                    name_entry.insert(format_ident!("{}{}", name.name, *value)).clone()
+        if let Some(ident) = self.rust_names.get(name) {
+            return ident.clone();
+        }
+
+        let ident = if self.colliding_names.contains(name) {
+            format_ident!("{}Ns{}", name.name, self.namespace_index(name))
+        } else {
+            format_ident!("{}", name.name)
+        };
+
+        self.rust_names.insert(name.clone(), ident.clone());
+        ident
+    }
+}
+
+fn get_ty_ident(ty: &str) -> Option<TokenStream> {
+    let ident = match ty {
+        "boolean" => format_ident!("bool"),
+        "byte" => format_ident!("i8"),
+        "short" => format_ident!("i16"),
+        "int" => format_ident!("i32"),
+        "long" => format_ident!("i64"),
+        "unsignedByte" => format_ident!("u8"),
+        "unsignedShort" => format_ident!("u16"),
+        "unsignedInt" => format_ident!("u32"),
+        "unsignedLong" => format_ident!("u64"),
+        "float" => format_ident!("f32"),
+        // xsd:decimal is arbitrary precision, f64 is a lossy approximation
+        "double" | "decimal" => format_ident!("f64"),
+        #[cfg(feature = "chrono")]
+        "dateTime" => return Some(quote! { suds_util::chrono::DateTime<suds_util::chrono::FixedOffset> }),
+        #[cfg(feature = "chrono")]
+        "date" => return Some(quote! { suds_util::chrono::NaiveDate }),
+        #[cfg(feature = "chrono")]
+        "time" => return Some(quote! { suds_util::chrono::NaiveTime }),
+        #[cfg(not(feature = "chrono"))]
+        "dateTime" => format_ident!("String"),
+        ty if is_string_type(ty) => format_ident!("String"),
+        "base64Binary" => return Some(quote! { Vec<u8> }),
+        "any" | "anyType" => return Some(quote! { suds_util::xml::AnyXml }),
+        _ => return None,
+    };
+
+    Some(quote! { #ident })
+}
+
+// QName could carry its namespace separately, but the prefixed form is enough for now
+fn is_string_type(ty: &str) -> bool {
+    matches!(
+        ty,
+        "string" | "normalizedString" | "token" | "language" | "Name" | "NCName" | "NMTOKEN" | "anyURI" | "QName"
+    )
+}
+
+fn codegen_to_text(ty: &str, value: TokenStream) -> TokenStream {
+    match ty {
+        // chrono's Display isn't the xsd lexical form, RFC 3339 is
+        #[cfg(feature = "chrono")]
+        "dateTime" => quote! { #value.to_rfc3339() },
+        "base64Binary" => quote! { suds_util::xml::to_base64(&#value) },
+        _ => quote! { #value.to_string() },
+    }
+}
+
+fn codegen_from_text(ty: &str) -> TokenStream {
+    match ty {
+        "base64Binary" => quote! { suds_util::xml::read_base64(reader, buffer)? },
+        #[cfg(feature = "chrono")]
+        "dateTime" => quote! { {
+            let value = suds_util::xml::expect_date_time(reader, buffer)?;
+            suds_util::xml::expect_end(reader, buffer)?;
+
+            value
+        } },
+        "anyType" => quote! { {
+            let value = <suds_util::xml::AnyXml as suds_util::xml::FromXml>::from_xml(reader, buffer)?;
+            suds_util::xml::expect_end(reader, buffer)?;
+
+            value
+        } },
+        _ => quote! { {
+            let value = suds_util::xml::expect_value(reader, buffer)?;
+            suds_util::xml::expect_end(reader, buffer)?;
+
+            value
+        } },
+    }
+}
+
+fn enum_variant_name(value: &str) -> String {
+    let mut name = value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+
+    match name.chars().next() {
+        Some(first) if first.is_ascii_alphabetic() => {
+            name.replace_range(..1, &first.to_ascii_uppercase().to_string())
+        }
+        Some(_) => name.insert_str(0, "Value"),
+        None => name.push_str("Empty"),
+    }
+
+    if name == "Self" {
+        name.push('_');
+    }
+
+    name
+}
+
+fn enum_variant_idents(values: &[String]) -> Vec<Ident> {
+    let mut counts = HashMap::<_, u32>::new();
+
+    values
+        .iter()
+        .map(|value| {
+            let name = enum_variant_name(value);
+
+            match counts.entry(name.clone()) {
+                Entry::Occupied(mut entry) => {
+                    *entry.get_mut() += 1;
+                    format_ident!("{}{}", name, entry.get())
                 }
-                Entry::Vacant(count_entry) => {
-                    count_entry.insert(0);
-                    name_entry.insert(format_ident!("{}", name.name)).clone()
+                Entry::Vacant(entry) => {
+                    entry.insert(0);
+                    format_ident!("{}", name)
                 }
             }
+        })
+        .collect()
+}
+
+fn codegen_doc(documentation: &Option<String>) -> TokenStream {
+    match documentation {
+        Some(documentation) => {
+            let documentation = documentation.trim();
+            quote! { #[doc = #documentation] }
+        }
+        None => quote! {},
+    }
+}
+
+fn derives(has_default: bool) -> TokenStream {
+    if has_default {
+        quote! { #[derive(Debug, Clone, PartialEq, Default)] }
+    } else {
+        quote! { #[derive(Debug, Clone, PartialEq)] }
+    }
+}
+
+// Bounds are parsed against the base type here, so a facet that doesn't fit it fails the build
+fn codegen_bound(ty: &str, value: &str) -> Result<TokenStream, String> {
+    let invalid = || format!("{:?} is not a valid xsd:{} bound", value, ty);
+
+    let (negative, literal) = match ty {
+        "byte" | "short" | "int" | "long" | "unsignedByte" | "unsignedShort" | "unsignedInt" | "unsignedLong" => {
+            let (min, max): (i128, i128) = match ty {
+                "byte" => (i8::MIN.into(), i8::MAX.into()),
+                "short" => (i16::MIN.into(), i16::MAX.into()),
+                "int" => (i32::MIN.into(), i32::MAX.into()),
+                "long" => (i64::MIN.into(), i64::MAX.into()),
+                "unsignedByte" => (0, u8::MAX.into()),
+                "unsignedShort" => (0, u16::MAX.into()),
+                "unsignedInt" => (0, u32::MAX.into()),
+                _ => (0, u64::MAX.into()),
+            };
+
+            let bound = value.trim().parse::<i128>().map_err(|_| invalid())?;
+
+            if bound < min || bound > max {
+                return Err(invalid());
+            }
+
+            (bound < 0, Literal::u128_unsuffixed(bound.unsigned_abs()))
+        }
+
+        "float" | "double" | "decimal" => {
+            let bound = value.trim().parse::<f64>().map_err(|_| invalid())?;
+
+            if !bound.is_finite() || (ty == "float" && !(bound as f32).is_finite()) {
+                return Err(invalid());
+            }
+
+            (bound < 0.0, Literal::f64_unsuffixed(bound.abs()))
+        }
+
+        _ => return Err(format!("Range facets aren't supported on xsd:{}", ty)),
+    };
+
+    Ok(if negative {
+        quote! { -#literal }
+    } else {
+        quote! { #literal }
+    })
+}
+
+fn codegen_facet_checks(ty: &str, facets: &wsdl::Facets) -> Vec<TokenStream> {
+    let mut checks = Vec::new();
+
+    let length = if ty == "base64Binary" {
+        quote! { self.0.len() }
+    } else {
+        quote! { self.0.to_string().chars().count() }
+    };
+
+    if let Some(min) = facets.min_length {
+        checks.push(quote! {
+            if #length < #min {
+                return Err(suds_util::error::ValidationError::Length(#length));
+            }
+        });
+    }
+
+    if let Some(max) = facets.max_length {
+        checks.push(quote! {
+            if #length > #max {
+                return Err(suds_util::error::ValidationError::Length(#length));
+            }
+        });
+    }
+
+    let bounds = [
+        (&facets.min_inclusive, quote! { < }),
+        (&facets.max_inclusive, quote! { > }),
+    ];
+
+    for (bound, comparison) in bounds {
+        if let Some(bound) = bound {
+            checks.push(match codegen_bound(ty, bound) {
+                Ok(bound) => quote! {
+                    if self.0 #comparison #bound {
+                        return Err(suds_util::error::ValidationError::Range(self.0.to_string()));
+                    }
+                },
+                Err(message) => quote! { compile_error!(#message); },
+            });
         }
     }
+
+    if !facets.patterns.is_empty() {
+        let text = codegen_to_text(ty, quote! { self.0 });
+        let patterns = &facets.patterns;
+
+        checks.push(quote! {
+            let text = #text;
+
+            if !suds_util::xml::matches_pattern(&text, &[#(#patterns),*]) {
+                return Err(suds_util::error::ValidationError::Pattern(text));
+            }
+        });
+    }
+
+    checks
+}
+
+fn type_has_default(kind: &wsdl::TypeKind, defaults: &HashSet<NamespacedName>) -> bool {
+    match kind {
+        wsdl::TypeKind::Simple(..) | wsdl::TypeKind::List(_) => true,
+        wsdl::TypeKind::Struct(fields) => fields.iter().all(|field| field_has_default(&field.ty, defaults)),
+        wsdl::TypeKind::Alias(alias) => get_ty_ident(&alias.name).is_some() || defaults.contains(alias),
+        wsdl::TypeKind::Enum(_) | wsdl::TypeKind::Choice(_) => false,
+    }
 }
 
-fn get_ty_ident(ty: &str) -> Option<Ident> {
+fn field_has_default(ty: &wsdl::FieldKind, defaults: &HashSet<NamespacedName>) -> bool {
     match ty {
-        "boolean" => Some(format_ident!("bool")),
-        "int" => Some(format_ident!("isize")),
-        "unsignedShort" => Some(format_ident!("u16")),
-        "unsignedInt" => Some(format_ident!("usize")),
-        "dateTime" | "string" => Some(format_ident!("String")),
-        _ => None,
+        wsdl::FieldKind::Type(name) | wsdl::FieldKind::Attribute(name) => {
+            get_ty_ident(&name.name).is_some() || defaults.contains(name)
+        }
+        wsdl::FieldKind::Inner(kind) => type_has_default(kind, defaults),
+        wsdl::FieldKind::Optional(_) | wsdl::FieldKind::List(_) | wsdl::FieldKind::Nillable(_) => true,
+        wsdl::FieldKind::Text(_) => true,
     }
 }
 
@@ -59,15 +421,33 @@ fn codegen_all(all: &[impl Codegen], state: &mut State) -> Vec<TokenStream> {
 
 pub fn codegen(definition: &types::Definition, namespaces: &Namespaces) -> TokenStream {
     let mut state = State::new();
-
-    let types = codegen_all(&definition.types, &mut state);
-    let messages = codegen_all(&definition.messages, &mut state);
-    let services = codegen_all(&definition.services, &mut state);
-
-    let namespace_attributes = namespaces
-        .namespaces()
+    state.namespaces = namespaces.namespaces().to_vec();
+    state.add_substitution_groups(&definition.substitution_groups);
+    state.add_default_types(&definition.types);
+    state.add_declared_types(&definition.types);
+    state.add_names(definition);
+
+    let types = state.sorted(&definition.types, |ty| &ty.name);
+    let substitution_groups = state.sorted(&definition.substitution_groups, |group| &group.head);
+    let messages = state.sorted(&definition.messages, |message| &message.name);
+    let port_types = state.sorted(&definition.port_types, |port_type| &port_type.name);
+    let services = state.sorted(&definition.services, |service| &service.name);
+
+    let types = codegen_all(&types, &mut state);
+    let substitution_groups = codegen_all(&substitution_groups, &mut state);
+    let messages = codegen_all(&messages, &mut state);
+    let port_types = codegen_all(&port_types, &mut state);
+    let registry = codegen_registry(&services, &mut state);
+    let services = codegen_all(&services, &mut state);
+
+    let mut sorted_namespaces = namespaces.namespaces().to_vec();
+    sorted_namespaces.sort();
+
+    // Unqualified names have no namespace to declare, but still take up an index
+    let namespace_attributes = sorted_namespaces
         .iter()
         .enumerate()
+        .filter(|(_, url)| !url.is_empty())
         .map(|(idx, url)| {
             let ns = format!("xmlns:ns{}", idx);
             quote! {.with_attributes([(#ns, #url)])}
@@ -75,6 +455,7 @@ pub fn codegen(definition: &types::Definition, namespaces: &Namespaces) -> Token
         .collect::<Vec<_>>();
 
     quote! {
+        #[allow(non_snake_case)]
         pub mod types {
             fn with_attributes<'a>(start: suds_util::xml::events::BytesStart<'a>) -> suds_util::xml::events::BytesStart<'a> {
                 start
@@ -82,14 +463,76 @@ pub fn codegen(definition: &types::Definition, namespaces: &Namespaces) -> Token
             }
 
             #(#types)*
+            #(#substitution_groups)*
         }
 
+        #[allow(non_snake_case)]
         pub mod messages {
             #(#messages)*
         }
 
+        #[allow(non_snake_case)]
         pub mod services {
+            #(#port_types)*
             #(#services)*
+            #registry
+        }
+    }
+}
+
+// Every port in the definition, constructed together so they can be passed around as one
+fn codegen_registry(services: &[types::Service], state: &mut State) -> TokenStream {
+    let mut fields = Vec::new();
+    let mut paths = Vec::new();
+    let mut counts = HashMap::<_, u32>::new();
+
+    for service in services {
+        let service_name = state.rust_name(&service.name);
+
+        for port in &service.ports {
+            let port_name = state.rust_name(&port.name);
+
+            // Ports of different services can share a name, so each field is prefixed with its service
+            let field = snake_case(&format!("{}_{}", service.name.name, port.name.name));
+            let field = match counts.entry(field.clone()) {
+                Entry::Occupied(mut entry) => {
+                    *entry.get_mut() += 1;
+                    format_ident!("{}{}", field, entry.get())
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(0);
+                    format_ident!("{}", field)
+                }
+            };
+
+            fields.push(field);
+            paths.push(quote! { #service_name::#port_name });
+        }
+    }
+
+    quote! {
+        pub struct ServiceRegistry {
+            #(pub #fields: #paths,)*
+        }
+
+        impl Default for ServiceRegistry {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl ServiceRegistry {
+            pub fn new() -> Self {
+                Self {
+                    #(#fields: #paths::new(),)*
+                }
+            }
+
+            pub fn with_client(client: suds_util::soap::Client) -> Self {
+                Self {
+                    #(#fields: #paths::with_client(client.clone()),)*
+                }
+            }
         }
     }
 }
@@ -101,21 +544,32 @@ impl Codegen for wsdl::Type {
         }
 
         let name = state.rust_name(&self.name);
+        let doc = codegen_doc(&self.documentation);
 
-        let to_xml_name = format!("ns{}:{}", self.name.index(), &self.name.name);
+        let to_xml_name = state.xml_name(&self.name);
         let from_xml_name = &self.name.name;
+        let from_xml_namespace = state.namespace(&self.name);
 
         match &self.kind {
-            wsdl::TypeKind::Simple(ty) => {
+            wsdl::TypeKind::Simple(ty, facets) => {
                 let inner_ty = get_ty_ident(&ty.name).unwrap();
+                let to_text = codegen_to_text(&ty.name, quote! { self.0 });
+                let from_text = codegen_from_text(&ty.name);
+                let derives = derives(true);
+                let checks = codegen_facet_checks(&ty.name, facets);
 
                 quote! {
-                    #[derive(Debug, Clone)]
+                    #doc
+                    #derives
                     pub struct #name(pub #inner_ty);
 
                     impl suds_util::xml::ToXml for #name {
-                        fn to_xml<W: std::io::Write>(&self, writer: &mut suds_util::xml::Writer<W>, mut top_level: bool) {
-                            let start = suds_util::xml::events::BytesStart::owned_name(#to_xml_name);
+                        fn to_xml<W: std::io::Write>(&self, writer: &mut suds_util::xml::Writer<W>, top_level: bool) {
+                            suds_util::xml::ToXml::to_xml_named(self, writer, #to_xml_name, top_level)
+                        }
+
+                        fn to_xml_named<W: std::io::Write>(&self, writer: &mut suds_util::xml::Writer<W>, name: &str, mut top_level: bool) {
+                            let start = suds_util::xml::events::BytesStart::owned_name(name);
 
                             let start = if top_level {
                                 with_attributes(start)
@@ -125,7 +579,7 @@ impl Codegen for wsdl::Type {
 
                             top_level = false;
 
-                            let string = format!("{}", 0);
+                            let string = #to_text;
                             let value = suds_util::xml::events::BytesText::from_plain_str(&string);
 
                             writer.write_event(suds_util::xml::events::Event::Start(start.to_borrowed())).unwrap();
@@ -135,32 +589,68 @@ impl Codegen for wsdl::Type {
                     }
 
                     impl suds_util::xml::FromXml for #name {
-                        fn from_xml<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>) -> Self {
-                            suds_util::xml::expect_start(reader, buffer, #from_xml_name).unwrap();
-                            let value = suds_util::xml::expect_value(reader, buffer).unwrap();
-                            suds_util::xml::expect_end(reader, buffer).unwrap();
+                        fn from_xml<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, suds_util::error::XmlError> {
+                            <Self as suds_util::xml::FromXml>::from_xml_named(reader, buffer, #from_xml_namespace, #from_xml_name)
+                        }
+
+                        fn from_xml_named<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>, namespace: &str, name: &str) -> Result<Self, suds_util::error::XmlError> {
+                            suds_util::xml::expect_start_ns(reader, buffer, namespace, name)?;
+                            let value = Self(#from_text);
+
+                            if reader.validates() {
+                                suds_util::xml::Validate::validate(&value).map_err(|source| suds_util::error::XmlError::Validation {
+                                    source,
+                                    position: reader.position(),
+                                })?;
+                            }
+
+                            Ok(value)
+                        }
+                    }
 
-                            Self(value)
+                    impl suds_util::xml::Validate for #name {
+                        fn validate(&self) -> Result<(), suds_util::error::ValidationError> {
+                            #(#checks)*
+                            Ok(())
                         }
                     }
 
+                    impl std::fmt::Display for #name {
+                        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                            f.write_str(&#to_text)
+                        }
+                    }
                 }
             }
 
             wsdl::TypeKind::Struct(fields) => {
+                let derives = derives(type_has_default(&self.kind, &state.default_types));
                 let member_fields = codegen_all(fields, state);
+                let to_xml_attributes = codegen_to_xml_attributes(fields);
                 let to_xml_fields = codegen_to_xml_fields(fields, state);
                 let from_xml_fields = codegen_from_xml_fields(fields, state);
+                let field_names = field_idents(fields);
+
+                let start = if fields.iter().any(|field| is_attribute(&field.ty)) {
+                    quote! { let start = }
+                } else {
+                    quote! {}
+                };
 
                 quote! {
-                    #[derive(Debug, Clone)]
+                    #doc
+                    #derives
                     pub struct #name {
                         #(#member_fields)*
                     }
 
                     impl suds_util::xml::ToXml for #name {
-                        fn to_xml<W: std::io::Write>(&self, writer: &mut suds_util::xml::Writer<W>, mut top_level: bool) {
-                            let start = suds_util::xml::events::BytesStart::owned_name(#to_xml_name);
+                        fn to_xml<W: std::io::Write>(&self, writer: &mut suds_util::xml::Writer<W>, top_level: bool) {
+                            suds_util::xml::ToXml::to_xml_named(self, writer, #to_xml_name, top_level)
+                        }
+
+                        fn to_xml_named<W: std::io::Write>(&self, writer: &mut suds_util::xml::Writer<W>, name: &str, mut top_level: bool) {
+                            let start = suds_util::xml::events::BytesStart::owned_name(name);
 
                             let start = if top_level {
                                 with_attributes(start)
@@ -168,6 +658,8 @@ impl Codegen for wsdl::Type {
                                 start
                             };
 
+                            #(#to_xml_attributes)*
+
                             top_level = false;
 
                             writer.write_event(suds_util::xml::events::Event::Start(start.to_borrowed())).unwrap();
@@ -177,150 +669,911 @@ impl Codegen for wsdl::Type {
                     }
 
                     impl suds_util::xml::FromXml for #name {
-                        fn from_xml<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>) -> Self {
-                            suds_util::xml::expect_start(reader, buffer, #from_xml_name).unwrap();
-                            let result = Self {
-                                #(#from_xml_fields)*
-                            };
-                            suds_util::xml::expect_end(reader, buffer).unwrap();
+                        fn from_xml<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, suds_util::error::XmlError> {
+                            <Self as suds_util::xml::FromXml>::from_xml_named(reader, buffer, #from_xml_namespace, #from_xml_name)
+                        }
 
-                            result
+                        fn from_xml_named<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>, namespace: &str, name: &str) -> Result<Self, suds_util::error::XmlError> {
+                            #start suds_util::xml::expect_start_ns(reader, buffer, namespace, name)?;
+                            #(#from_xml_fields)*
+                            suds_util::xml::expect_end(reader, buffer)?;
+
+                            Ok(Self {
+                                #(#field_names,)*
+                            })
                         }
                     }
                 }
             }
 
-            wsdl::TypeKind::Alias(alias) => {
-                if *alias != self.name {
-                    if let Some(ident) = get_ty_ident(&alias.name) {
-                        quote! {pub type #name = #ident;}
-                    } else {
-                        let alias = state.rust_name(&alias);
-                        quote! {pub type #name = #alias;}
+            wsdl::TypeKind::Enum(values) => {
+                let derives = derives(false);
+                let variants = enum_variant_idents(values);
+
+                quote! {
+                    #doc
+                    #derives
+                    pub enum #name {
+                        #(#variants,)*
                     }
-                } else {
-                    quote! {}
-                }
-            }
-        }
-    }
-}
 
-impl Codegen for wsdl::Field {
-    fn codegen(&self, state: &mut State) -> TokenStream {
-        let name = format_ident!("{}", &self.name.name);
+                    impl #name {
+                        pub fn as_str(&self) -> &str {
+                            match self {
+                                #(Self::#variants => #values,)*
+                            }
+                        }
+                    }
 
-        let ty = match &self.ty {
-            wsdl::FieldKind::Type(name) => {
-                if let Some(ident) = get_ty_ident(&name.name) {
-                    quote! {#ident}
-                } else {
-                    let ident = state.rust_name(&name);
-                    quote! { super::types::#ident }
-                }
-            }
+                    impl suds_util::xml::ToXml for #name {
+                        fn to_xml<W: std::io::Write>(&self, writer: &mut suds_util::xml::Writer<W>, top_level: bool) {
+                            suds_util::xml::ToXml::to_xml_named(self, writer, #to_xml_name, top_level)
+                        }
 
-            wsdl::FieldKind::Inner(wsdl::TypeKind::Struct(fields)) => {
-                if fields.len() != 1 {
-                    unimplemented!()
-                }
+                        fn to_xml_named<W: std::io::Write>(&self, writer: &mut suds_util::xml::Writer<W>, name: &str, top_level: bool) {
+                            let start = suds_util::xml::events::BytesStart::owned_name(name);
 
-                let mut field = fields.last().unwrap().clone();
-                field.name = self.name.clone();
-                return field.codegen(state);
-            }
+                            let start = if top_level {
+                                with_attributes(start)
+                            } else {
+                                start
+                            };
 
-            _ => unimplemented!(),
-        };
+                            let value = suds_util::xml::events::BytesText::from_plain_str(self.as_str());
 
-        quote! {
-            pub #name: #ty,
-        }
-    }
-}
+                            writer.write_event(suds_util::xml::events::Event::Start(start.to_borrowed())).unwrap();
+                            writer.write_event(suds_util::xml::events::Event::Text(value)).unwrap();
+                            writer.write_event(suds_util::xml::events::Event::End(start.to_end())).unwrap();
+                        }
+                    }
 
-fn codegen_to_xml_field(field: &wsdl::Field, state: &mut State) -> TokenStream {
-    let name = format_ident!("{}", &field.name.name);
-    let xml_name = format!("ns{}:{}", field.name.index(), &field.name.name);
+                    impl suds_util::xml::FromXml for #name {
+                        fn from_xml<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, suds_util::error::XmlError> {
+                            <Self as suds_util::xml::FromXml>::from_xml_named(reader, buffer, #from_xml_namespace, #from_xml_name)
+                        }
 
-    match &field.ty {
-        wsdl::FieldKind::Type(ty) => if get_ty_ident(&ty.name).is_some() {
-            quote! { {
-                let start = suds_util::xml::events::BytesStart::owned_name(#xml_name);
-                let string = format!("{}", self.#name);
-                let value = suds_util::xml::events::BytesText::from_plain_str(&string);
-                writer.write_event(suds_util::xml::events::Event::Start(start.to_borrowed())).unwrap();
-                writer.write_event(suds_util::xml::events::Event::Text(value)).unwrap();
-                writer.write_event(suds_util::xml::events::Event::End(start.to_end())).unwrap();
-            } }
-        } else {
-            quote! { self.#name.to_xml(writer, top_level); }
-        }
+                        fn from_xml_named<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>, namespace: &str, name: &str) -> Result<Self, suds_util::error::XmlError> {
+                            suds_util::xml::expect_start_ns(reader, buffer, namespace, name)?;
+                            let value: String = suds_util::xml::expect_value(reader, buffer)?;
+                            suds_util::xml::expect_end(reader, buffer)?;
+
+                            match value.as_str() {
+                                #(#values => Ok(Self::#variants),)*
+                                _ => Err(suds_util::error::XmlError::InvalidValue {
+                                    value: value.clone(),
+                                    position: reader.position(),
+                                }),
+                            }
+                        }
+                    }
 
-        wsdl::FieldKind::Inner(wsdl::TypeKind::Struct(fields)) => {
-            if fields.len() != 1 {
-                unimplemented!()
-            }
+                    // Only values from the enumeration can be represented, so there is nothing left to check
+                    impl suds_util::xml::Validate for #name {
+                        fn validate(&self) -> Result<(), suds_util::error::ValidationError> {
+                            Ok(())
+                        }
+                    }
 
-            let mut inner = fields.last().unwrap().clone();
-            inner.name = field.name.clone();
-            codegen_to_xml_field(&inner, state)
-        }
+                    impl std::fmt::Display for #name {
+                        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                            f.write_str(self.as_str())
+                        }
+                    }
 
-        _ => unimplemented!(),
-    }
-}
+                    impl std::convert::TryFrom<&str> for #name {
+                        type Error = suds_util::error::ValidationError;
 
-fn codegen_to_xml_fields(fields: &[wsdl::Field], state: &mut State) -> Vec<TokenStream> {
-    fields.iter().map(|field| codegen_to_xml_field(field, state)).collect()
-}
+                        fn try_from(value: &str) -> Result<Self, Self::Error> {
+                            match value {
+                                #(#values => Ok(Self::#variants),)*
+                                _ => Err(suds_util::error::ValidationError::Enumeration(value.to_owned())),
+                            }
+                        }
+                    }
 
-fn codegen_from_xml_field(field: &wsdl::Field, state: &mut State) -> TokenStream {
-    let name = format_ident!("{}", &field.name.name);
-    let xml_name = &field.name.name;
+                    impl std::str::FromStr for #name {
+                        type Err = suds_util::error::ValidationError;
 
-    match &field.ty {
-        wsdl::FieldKind::Type(ty) => if get_ty_ident(&ty.name).is_some() {
-            quote! { #name: {
-                suds_util::xml::expect_start(reader, buffer, #xml_name).unwrap();
-                let value = suds_util::xml::expect_value(reader, buffer).unwrap();
-                suds_util::xml::expect_end(reader, buffer).unwrap();
+                        fn from_str(value: &str) -> Result<Self, Self::Err> {
+                            <Self as std::convert::TryFrom<&str>>::try_from(value)
+                        }
+                    }
+                }
+            }
 
-                value
-            }, }
-        } else {
-            let ident = state.rust_name(&ty);
-            quote! { #name: super::types::#ident::from_xml(reader, buffer), }
-        },
+            wsdl::TypeKind::Choice(fields) => {
+                let names = fields.iter().map(|field| field.name.name.clone()).collect::<Vec<_>>();
+                let variants = enum_variant_idents(&names);
+                let derives = derives(false);
 
-        wsdl::FieldKind::Inner(wsdl::TypeKind::Struct(fields)) => {
-            if fields.len() != 1 {
-                unimplemented!()
-            }
+                let variant_tys = fields
+                    .iter()
+                    .map(|field| codegen_field_ty(&field.ty, &field.name, state))
+                    .collect::<Vec<_>>();
 
-            let mut inner = fields.last().unwrap().clone();
-            inner.name = field.name.clone();
-            codegen_from_xml_field(&inner, state)
-        }
+                let to_xml_variants = fields
+                    .iter()
+                    .map(|field| {
+                        codegen_to_xml_value(&field.ty, &field.name, quote! { value }, state)
+                    })
+                    .collect::<Vec<_>>();
 
-        _ => unimplemented!(),
-    }
-}
+                let from_xml_variants = fields
+                    .iter()
+                    .map(|field| codegen_from_xml_value(&field.ty, &field.name, state))
+                    .collect::<Vec<_>>();
 
-fn codegen_from_xml_fields(fields: &[wsdl::Field], state: &mut State) -> Vec<TokenStream> {
-    fields.iter().map(|field| codegen_from_xml_field(field, state)).collect()
-}
+                let namespaces = fields.iter().map(|field| state.namespace(&field.name)).collect::<Vec<_>>();
 
-impl Codegen for wsdl::Message {
-    fn codegen(&self, state: &mut State) -> TokenStream {
-        let name = state.rust_name(&self.name);
-        let fields = codegen_all(&self.parts, state);
+                quote! {
+                    #doc
+                    #derives
+                    pub enum #name {
+                        #(#variants(#variant_tys),)*
+                    }
 
-        let to_xml_fields = codegen_to_xml_fields(&self.parts, state);
-        let from_xml_fields = codegen_from_xml_fields(&self.parts, state);
+                    impl suds_util::xml::ToXml for #name {
+                        fn to_xml<W: std::io::Write>(&self, writer: &mut suds_util::xml::Writer<W>, top_level: bool) {
+                            suds_util::xml::ToXml::to_xml_named(self, writer, #to_xml_name, top_level)
+                        }
+
+                        fn to_xml_named<W: std::io::Write>(&self, writer: &mut suds_util::xml::Writer<W>, name: &str, mut top_level: bool) {
+                            let start = suds_util::xml::events::BytesStart::owned_name(name);
+
+                            let start = if top_level {
+                                with_attributes(start)
+                            } else {
+                                start
+                            };
+
+                            top_level = false;
+
+                            writer.write_event(suds_util::xml::events::Event::Start(start.to_borrowed())).unwrap();
+
+                            match self {
+                                #(Self::#variants(value) => { #to_xml_variants })*
+                            }
+
+                            writer.write_event(suds_util::xml::events::Event::End(start.to_end())).unwrap();
+                        }
+                    }
+
+                    impl suds_util::xml::FromXml for #name {
+                        fn from_xml<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, suds_util::error::XmlError> {
+                            <Self as suds_util::xml::FromXml>::from_xml_named(reader, buffer, #from_xml_namespace, #from_xml_name)
+                        }
+
+                        fn from_xml_named<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>, namespace: &str, name: &str) -> Result<Self, suds_util::error::XmlError> {
+                            suds_util::xml::expect_start_ns(reader, buffer, namespace, name)?;
+
+                            #(
+                                if suds_util::xml::is_next_start_ns(reader, buffer, #namespaces, #names)? {
+                                    let result = Self::#variants(#from_xml_variants);
+                                    suds_util::xml::expect_end(reader, buffer)?;
+
+                                    return Ok(result);
+                                }
+                            )*
+
+                            Err(suds_util::error::XmlError::ExpectedStart {
+                                name: name.to_owned(),
+                                position: reader.position(),
+                            })
+                        }
+                    }
+                }
+            }
+
+            wsdl::TypeKind::Alias(alias) => {
+                if *alias != self.name {
+                    if let Some(ident) = get_ty_ident(&alias.name) {
+                        quote! {#doc pub type #name = #ident;}
+                    } else {
+                        let alias = state.rust_name(&alias);
+                        quote! {#doc pub type #name = #alias;}
+                    }
+                } else {
+                    // An element typed by the same-named type, which is generated separately
+                    state.added_types.remove(&self.name);
+
+                    if state.declared_types.contains(&self.name) {
+                        quote! {}
+                    } else {
+                        let message = format!("Type {} is declared as an alias of itself", &self.name.name);
+                        quote! { compile_error!(#message); }
+                    }
+                }
+            }
+
+            wsdl::TypeKind::List(item) => {
+                let item_ty = if let Some(item_ty) = get_ty_ident(&item.name) {
+                    item_ty
+                } else {
+                    let message = format!("List type {} has an unsupported item type {}", &self.name.name, &item.name);
+                    return quote! { compile_error!(#message); };
+                };
+
+                let to_text = codegen_to_text(&item.name, quote! { value });
+                let derives = derives(true);
+
+                quote! {
+                    #doc
+                    #derives
+                    pub struct #name(pub Vec<#item_ty>);
+
+                    impl suds_util::xml::ToXml for #name {
+                        fn to_xml<W: std::io::Write>(&self, writer: &mut suds_util::xml::Writer<W>, top_level: bool) {
+                            suds_util::xml::ToXml::to_xml_named(self, writer, #to_xml_name, top_level)
+                        }
+
+                        fn to_xml_named<W: std::io::Write>(&self, writer: &mut suds_util::xml::Writer<W>, name: &str, top_level: bool) {
+                            let start = suds_util::xml::events::BytesStart::owned_name(name);
+
+                            let start = if top_level {
+                                with_attributes(start)
+                            } else {
+                                start
+                            };
+
+                            let string = self.0.iter().map(|value| #to_text).collect::<Vec<_>>().join(" ");
+                            let value = suds_util::xml::events::BytesText::from_plain_str(&string);
+
+                            writer.write_event(suds_util::xml::events::Event::Start(start.to_borrowed())).unwrap();
+                            writer.write_event(suds_util::xml::events::Event::Text(value)).unwrap();
+                            writer.write_event(suds_util::xml::events::Event::End(start.to_end())).unwrap();
+                        }
+                    }
+
+                    impl suds_util::xml::FromXml for #name {
+                        fn from_xml<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, suds_util::error::XmlError> {
+                            <Self as suds_util::xml::FromXml>::from_xml_named(reader, buffer, #from_xml_namespace, #from_xml_name)
+                        }
+
+                        fn from_xml_named<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>, namespace: &str, name: &str) -> Result<Self, suds_util::error::XmlError> {
+                            suds_util::xml::expect_start_ns(reader, buffer, namespace, name)?;
+                            let text: String = suds_util::xml::expect_value(reader, buffer)?;
+                            suds_util::xml::expect_end(reader, buffer)?;
+
+                            let values = text
+                                .split_whitespace()
+                                .map(|value| value.parse().map_err(|_| suds_util::error::XmlError::InvalidValue {
+                                    value: value.to_owned(),
+                                    position: reader.position(),
+                                }))
+                                .collect::<Result<Vec<_>, _>>()?;
+
+                            Ok(Self(values))
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Codegen for wsdl::SubstitutionGroup {
+    fn codegen(&self, state: &mut State) -> TokenStream {
+        let name = state.group_name(&self.head);
+        let head_name = &self.head.name;
+
+        let members = std::iter::once(&self.head).chain(&self.members).collect::<Vec<_>>();
+        let variants = members.iter().map(|member| state.rust_name(member)).collect::<Vec<_>>();
+        let namespaces = members.iter().map(|member| state.namespace(member)).collect::<Vec<_>>();
+        let names = members.iter().map(|member| &member.name).collect::<Vec<_>>();
+
+        quote! {
+            #[derive(Debug, Clone, PartialEq)]
+            pub enum #name {
+                #(#variants(#variants),)*
+            }
+
+            impl #name {
+                pub fn is_next<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>) -> Result<bool, suds_util::error::XmlError> {
+                    #(
+                        if suds_util::xml::is_next_start_ns(reader, buffer, #namespaces, #names)? {
+                            return Ok(true);
+                        }
+                    )*
+
+                    Ok(false)
+                }
+            }
+
+            impl suds_util::xml::ToXml for #name {
+                fn to_xml<W: std::io::Write>(&self, writer: &mut suds_util::xml::Writer<W>, top_level: bool) {
+                    match self {
+                        #(Self::#variants(value) => suds_util::xml::ToXml::to_xml(value, writer, top_level),)*
+                    }
+                }
+            }
+
+            impl suds_util::xml::FromXml for #name {
+                fn from_xml<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, suds_util::error::XmlError> {
+                    #(
+                        if suds_util::xml::is_next_start_ns(reader, buffer, #namespaces, #names)? {
+                            return Ok(Self::#variants(<#variants as suds_util::xml::FromXml>::from_xml(reader, buffer)?));
+                        }
+                    )*
+
+                    Err(suds_util::error::XmlError::ExpectedStart {
+                        name: #head_name.to_owned(),
+                        position: reader.position(),
+                    })
+                }
+            }
+        }
+    }
+}
+
+impl Codegen for wsdl::Field {
+    fn codegen(&self, state: &mut State) -> TokenStream {
+        if let Some(fields) = flattened_fields(&self.ty) {
+            let fields = codegen_all(fields, state);
+            return quote! { #(#fields)* };
+        }
+
+        let name = member_ident(&self.name.name);
+        let ty = codegen_field_ty(&self.ty, &self.name, state);
+        let doc = codegen_doc(&self.documentation);
+
+        quote! {
+            #doc
+            pub #name: #ty,
+        }
+    }
+}
+
+pub fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut result = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        // Word boundaries are lower to upper case, or the last capital of an acronym
+        let boundary = i > 0
+            && c.is_ascii_uppercase()
+            && (!chars[i - 1].is_ascii_uppercase()
+                || chars.get(i + 1).is_some_and(|next| next.is_ascii_lowercase()));
+
+        if boundary && !result.ends_with('_') {
+            result.push('_');
+        }
+
+        result.push(if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' });
+    }
+
+    result
+}
+
+// Fields and methods keep their XML names unless converted, the XML name is always used on the wire
+fn member_ident(name: &str) -> Ident {
+    #[cfg(feature = "snake-case")]
+    let name = match snake_case(name) {
+        name if is_keyword(&name) => format!("{}_", name),
+        name => name,
+    };
+
+    format_ident!("{}", name)
+}
+
+#[cfg(feature = "snake-case")]
+fn is_keyword(name: &str) -> bool {
+    matches!(
+        name,
+        "abstract" | "as" | "async" | "await" | "become" | "box" | "break" | "const" | "continue"
+            | "crate" | "do" | "dyn" | "else" | "enum" | "extern" | "false" | "final" | "fn"
+            | "for" | "if" | "impl" | "in" | "let" | "loop" | "macro" | "match" | "mod" | "move"
+            | "mut" | "override" | "priv" | "pub" | "ref" | "return" | "self" | "static"
+            | "struct" | "super" | "trait" | "true" | "try" | "type" | "typeof" | "unsafe"
+            | "unsized" | "use" | "virtual" | "where" | "while" | "yield"
+    )
+}
+
+fn flattened_fields(ty: &wsdl::FieldKind) -> Option<&[wsdl::Field]> {
+    match ty {
+        wsdl::FieldKind::Inner(wsdl::TypeKind::Struct(fields)) if fields.len() > 1 => Some(fields),
+        _ => None,
+    }
+}
+
+fn field_idents(fields: &[wsdl::Field]) -> Vec<Ident> {
+    fields
+        .iter()
+        .flat_map(|field| match flattened_fields(&field.ty) {
+            Some(fields) => field_idents(fields),
+            None => vec![member_ident(&field.name.name)],
+        })
+        .collect()
+}
+
+fn leaf_fields(fields: &[wsdl::Field]) -> Vec<&wsdl::Field> {
+    fields
+        .iter()
+        .flat_map(|field| match flattened_fields(&field.ty) {
+            Some(fields) => leaf_fields(fields),
+            None => vec![field],
+        })
+        .collect()
+}
+
+fn inner_field_kind(fields: &[wsdl::Field]) -> Option<&wsdl::FieldKind> {
+    match fields {
+        [field] => Some(&field.ty),
+        _ => None,
+    }
+}
+
+// Inline types are only supported when they wrap a single field, anything else fails the build
+fn unsupported_field(name: &NamespacedName) -> TokenStream {
+    let message = format!("Unsupported inline type for `{}`", name.name);
+    quote! { compile_error!(#message) }
+}
+
+fn text_base(ty: &NamespacedName) -> &str {
+    match ty.name.as_str() {
+        "base64Binary" | "any" | "anyType" => "string",
+        name if get_ty_ident(name).is_some() => name,
+        // Named simple types write their own element, so text extending them is kept as a string
+        _ => "string",
+    }
+}
+
+fn is_attribute(ty: &wsdl::FieldKind) -> bool {
+    match ty {
+        wsdl::FieldKind::Attribute(_) => true,
+        wsdl::FieldKind::Optional(inner) => is_attribute(inner),
+        _ => false,
+    }
+}
+
+fn codegen_field_ty(ty: &wsdl::FieldKind, name: &NamespacedName, state: &mut State) -> TokenStream {
+    match ty {
+        wsdl::FieldKind::Type(name) if state.substitution_heads.contains(name) => {
+            let group = state.group_name(name);
+            quote! { super::types::#group }
+        }
+
+        wsdl::FieldKind::Type(name) | wsdl::FieldKind::Attribute(name) => {
+            if let Some(ident) = get_ty_ident(&name.name) {
+                quote! {#ident}
+            } else {
+                let ident = state.rust_name(&name);
+                quote! { super::types::#ident }
+            }
+        }
+
+        wsdl::FieldKind::Inner(wsdl::TypeKind::Struct(fields)) => {
+            match inner_field_kind(fields) {
+                Some(inner) => codegen_field_ty(inner, name, state),
+                None => unsupported_field(name),
+            }
+        }
+
+        wsdl::FieldKind::Text(ty) => get_ty_ident(text_base(ty)).unwrap(),
+
+        wsdl::FieldKind::Optional(inner) | wsdl::FieldKind::Nillable(inner) => {
+            let inner = codegen_field_ty(inner, name, state);
+            quote! { Option<#inner> }
+        }
+
+        wsdl::FieldKind::List(inner) => {
+            let inner = codegen_field_ty(inner, name, state);
+            quote! { Vec<#inner> }
+        }
+
+        _ => unsupported_field(name),
+    }
+}
+
+fn codegen_to_xml_value(ty: &wsdl::FieldKind, name: &NamespacedName, value: TokenStream, state: &mut State) -> TokenStream {
+    let xml_name = state.xml_name(name);
+
+    match ty {
+        wsdl::FieldKind::Type(ty) if ty.name == "any" => {
+            quote! { suds_util::xml::ToXml::to_xml(#value, writer, false); }
+        }
+
+        wsdl::FieldKind::Type(ty) if ty.name == "anyType" => quote! { {
+            let start = suds_util::xml::events::BytesStart::owned_name(#xml_name);
+            writer.write_event(suds_util::xml::events::Event::Start(start.to_borrowed())).unwrap();
+            suds_util::xml::ToXml::to_xml(#value, writer, false);
+            writer.write_event(suds_util::xml::events::Event::End(start.to_end())).unwrap();
+        } },
+
+        wsdl::FieldKind::Type(ty) if ty.name == "base64Binary" => quote! { {
+            let start = suds_util::xml::events::BytesStart::owned_name(#xml_name);
+            writer.write_event(suds_util::xml::events::Event::Start(start.to_borrowed())).unwrap();
+            suds_util::xml::write_base64(writer, #value);
+            writer.write_event(suds_util::xml::events::Event::End(start.to_end())).unwrap();
+        } },
+
+        wsdl::FieldKind::Type(ty) => if get_ty_ident(&ty.name).is_some() {
+            let to_text = codegen_to_text(&ty.name, value);
+            quote! { {
+                let start = suds_util::xml::events::BytesStart::owned_name(#xml_name);
+                let string = #to_text;
+                let value = suds_util::xml::events::BytesText::from_plain_str(&string);
+                writer.write_event(suds_util::xml::events::Event::Start(start.to_borrowed())).unwrap();
+                writer.write_event(suds_util::xml::events::Event::Text(value)).unwrap();
+                writer.write_event(suds_util::xml::events::Event::End(start.to_end())).unwrap();
+            } }
+        } else if state.namespace(name).is_empty() {
+            // Unqualified elements such as RPC parts are named after the part rather than the type
+            quote! { suds_util::xml::ToXml::to_xml_named(#value, writer, #xml_name, top_level); }
+        } else {
+            quote! { suds_util::xml::ToXml::to_xml(#value, writer, top_level); }
+        }
+
+        wsdl::FieldKind::Inner(wsdl::TypeKind::Struct(fields)) => {
+            match inner_field_kind(fields) {
+                Some(inner) => codegen_to_xml_value(inner, name, value, state),
+                None => {
+                    let error = unsupported_field(name);
+                    quote! { #error; }
+                }
+            }
+        }
+
+        wsdl::FieldKind::Optional(inner) => {
+            let inner = codegen_to_xml_value(inner, name, quote! { value }, state);
+            quote! {
+                if let Some(value) = #value {
+                    #inner
+                }
+            }
+        }
+
+        wsdl::FieldKind::Nillable(inner) => {
+            let inner = codegen_to_xml_value(inner, name, quote! { value }, state);
+            quote! {
+                if let Some(value) = #value {
+                    #inner
+                } else {
+                    let start = suds_util::xml::events::BytesStart::owned_name(#xml_name).with_attributes([
+                        ("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance"),
+                        ("xsi:nil", "true"),
+                    ]);
+                    writer.write_event(suds_util::xml::events::Event::Empty(start)).unwrap();
+                }
+            }
+        }
+
+        wsdl::FieldKind::List(inner) => {
+            let inner = codegen_to_xml_value(inner, name, quote! { value }, state);
+            quote! {
+                for value in #value {
+                    #inner
+                }
+            }
+        }
+
+        _ => {
+            let error = unsupported_field(name);
+            quote! { #error; }
+        }
+    }
+}
+
+fn codegen_to_xml_attributes(fields: &[wsdl::Field]) -> Vec<TokenStream> {
+    fields
+        .iter()
+        .filter(|field| is_attribute(&field.ty))
+        .map(|field| {
+            let name = member_ident(&field.name.name);
+            let xml_name = &field.name.name;
+
+            match &field.ty {
+                wsdl::FieldKind::Optional(inner) => {
+                    let to_text = match inner.as_ref() {
+                        wsdl::FieldKind::Attribute(ty) => codegen_to_text(&ty.name, quote! { value }),
+                        _ => unreachable!(),
+                    };
+
+                    quote! {
+                        let start = if let Some(value) = &self.#name {
+                            start.with_attributes([(#xml_name, #to_text.as_str())])
+                        } else {
+                            start
+                        };
+                    }
+                }
+
+                wsdl::FieldKind::Attribute(ty) => {
+                    let to_text = codegen_to_text(&ty.name, quote! { self.#name });
+
+                    quote! {
+                        let start = start.with_attributes([(#xml_name, #to_text.as_str())]);
+                    }
+                }
+
+                _ => unreachable!(),
+            }
+        })
+        .collect()
+}
+
+fn codegen_to_xml_fields(fields: &[wsdl::Field], state: &mut State) -> Vec<TokenStream> {
+    fields
+        .iter()
+        .filter(|field| !is_attribute(&field.ty))
+        .map(|field| {
+            let name = member_ident(&field.name.name);
+            let xml_name = state.xml_name(&field.name);
+
+            if let wsdl::FieldKind::Text(ty) = &field.ty {
+                let to_text = codegen_to_text(text_base(ty), quote! { self.#name });
+
+                return quote! { {
+                    let string = #to_text;
+                    let value = suds_util::xml::events::BytesText::from_plain_str(&string);
+                    writer.write_event(suds_util::xml::events::Event::Text(value)).unwrap();
+                } };
+            }
+
+            if let Some(fields) = flattened_fields(&field.ty) {
+                let to_xml_attributes = codegen_to_xml_attributes(fields);
+                let to_xml_fields = codegen_to_xml_fields(fields, state);
+
+                return quote! { {
+                    let start = suds_util::xml::events::BytesStart::owned_name(#xml_name);
+                    #(#to_xml_attributes)*
+
+                    writer.write_event(suds_util::xml::events::Event::Start(start.to_borrowed())).unwrap();
+                    #(#to_xml_fields)*
+                    writer.write_event(suds_util::xml::events::Event::End(start.to_end())).unwrap();
+                } };
+            }
+
+            codegen_to_xml_value(&field.ty, &field.name, quote! { &self.#name }, state)
+        })
+        .collect()
+}
+
+fn is_empty_absent(ty: &wsdl::FieldKind) -> bool {
+    match ty {
+        wsdl::FieldKind::Type(ty) => {
+            get_ty_ident(&ty.name).is_some()
+                && !is_string_type(&ty.name)
+                && !matches!(ty.name.as_str(), "base64Binary" | "any" | "anyType")
+        }
+        _ => false,
+    }
+}
+
+fn codegen_is_next(ty: &wsdl::FieldKind, name: &NamespacedName, state: &mut State) -> TokenStream {
+    match ty {
+        wsdl::FieldKind::Type(ty) if state.substitution_heads.contains(ty) => {
+            let group = state.group_name(ty);
+            quote! { super::types::#group::is_next(reader, buffer)? }
+        }
+
+        _ => {
+            let xml_name = &name.name;
+            let namespace = state.namespace(name);
+            quote! { suds_util::xml::is_next_start_ns(reader, buffer, #namespace, #xml_name)? }
+        }
+    }
+}
+
+fn codegen_from_xml_value(ty: &wsdl::FieldKind, name: &NamespacedName, state: &mut State) -> TokenStream {
+    let xml_name = &name.name;
+    let namespace = state.namespace(name);
+
+    match ty {
+        wsdl::FieldKind::Type(ty) if ty.name == "any" => {
+            quote! { <suds_util::xml::AnyXml as suds_util::xml::FromXml>::from_xml(reader, buffer)? }
+        }
+
+        wsdl::FieldKind::Type(ty) if state.substitution_heads.contains(ty) => {
+            let group = state.group_name(ty);
+            quote! { <super::types::#group as suds_util::xml::FromXml>::from_xml(reader, buffer)? }
+        }
+
+        wsdl::FieldKind::Type(ty) => if get_ty_ident(&ty.name).is_some() {
+            let from_text = codegen_from_text(&ty.name);
+            quote! { {
+                suds_util::xml::expect_start_ns(reader, buffer, #namespace, #xml_name)?;
+                #from_text
+            } }
+        } else if namespace.is_empty() {
+            let ident = state.rust_name(&ty);
+            quote! { <super::types::#ident as suds_util::xml::FromXml>::from_xml_named(reader, buffer, #namespace, #xml_name)? }
+        } else {
+            let ident = state.rust_name(&ty);
+            quote! { <super::types::#ident as suds_util::xml::FromXml>::from_xml(reader, buffer)? }
+        },
+
+        wsdl::FieldKind::Inner(wsdl::TypeKind::Struct(fields)) => {
+            match inner_field_kind(fields) {
+                Some(inner) => codegen_from_xml_value(inner, name, state),
+                None => unsupported_field(name),
+            }
+        }
+
+        // An optional value sent as an empty element (such as <count/>) is treated as absent
+        wsdl::FieldKind::Optional(inner) if is_empty_absent(inner) => {
+            quote! {
+                if suds_util::xml::is_next_start_ns(reader, buffer, #namespace, #xml_name)? {
+                    suds_util::xml::expect_start_ns(reader, buffer, #namespace, #xml_name)?;
+                    let value = suds_util::xml::expect_optional_value(reader, buffer)?;
+                    suds_util::xml::expect_end(reader, buffer)?;
+
+                    value
+                } else {
+                    None
+                }
+            }
+        }
+
+        wsdl::FieldKind::Optional(inner) => {
+            let is_next = codegen_is_next(inner, name, state);
+            let inner = codegen_from_xml_value(inner, name, state);
+            quote! {
+                if #is_next {
+                    Some(#inner)
+                } else {
+                    None
+                }
+            }
+        }
+
+        wsdl::FieldKind::Nillable(inner) => {
+            let inner = codegen_from_xml_value(inner, name, state);
+            quote! {
+                if !suds_util::xml::is_next_start_ns(reader, buffer, #namespace, #xml_name)? {
+                    None
+                } else if suds_util::xml::is_next_nil(reader, buffer)? {
+                    suds_util::xml::expect_start_ns(reader, buffer, #namespace, #xml_name)?;
+                    suds_util::xml::expect_end(reader, buffer)?;
+                    None
+                } else {
+                    Some(#inner)
+                }
+            }
+        }
+
+        wsdl::FieldKind::List(inner) => {
+            let is_next = codegen_is_next(inner, name, state);
+            let inner = codegen_from_xml_value(inner, name, state);
+            quote! { {
+                let mut values = Vec::new();
+
+                while #is_next {
+                    values.push(#inner);
+                }
+
+                values
+            } }
+        }
+
+        _ => unsupported_field(name),
+    }
+}
+
+fn codegen_from_xml_fields(fields: &[wsdl::Field], state: &mut State) -> Vec<TokenStream> {
+    // Attributes are taken from the start tag before reading any of the content
+    let (attributes, content): (Vec<_>, Vec<_>) =
+        fields.iter().partition(|field| is_attribute(&field.ty));
+
+    attributes
+        .into_iter()
+        .chain(content)
+        .map(|field| {
+            let name = member_ident(&field.name.name);
+            let xml_name = &field.name.name;
+
+            match &field.ty {
+                wsdl::FieldKind::Attribute(_) => quote! {
+                    let #name = suds_util::xml::expect_attribute(reader, &start, #xml_name)?;
+                },
+
+                wsdl::FieldKind::Optional(inner) if is_attribute(inner) => quote! {
+                    let #name = suds_util::xml::get_attribute(reader, &start, #xml_name)?;
+                },
+
+                wsdl::FieldKind::Text(_) => quote! {
+                    let #name = suds_util::xml::expect_value(reader, buffer)?;
+                },
+
+                ty => if let Some(fields) = flattened_fields(ty) {
+                    let namespace = state.namespace(&field.name);
+                    let names = field_idents(fields);
+                    let from_xml_fields = codegen_from_xml_fields(fields, state);
+
+                    let start = if fields.iter().any(|field| is_attribute(&field.ty)) {
+                        quote! { let start = }
+                    } else {
+                        quote! {}
+                    };
+
+                    quote! {
+                        let (#(#names,)*) = {
+                            #start suds_util::xml::expect_start_ns(reader, buffer, #namespace, #xml_name)?;
+                            #(#from_xml_fields)*
+                            suds_util::xml::expect_end(reader, buffer)?;
+
+                            (#(#names,)*)
+                        };
+                    }
+                } else {
+                    let value = codegen_from_xml_value(ty, &field.name, state);
+                    quote! { let #name = #value; }
+                }
+            }
+        })
+        .collect()
+}
+
+impl Codegen for wsdl::Message {
+    fn codegen(&self, state: &mut State) -> TokenStream {
+        let name = state.rust_name(&self.name);
+        let derives = derives(
+            self.parts
+                .iter()
+                .all(|part| field_has_default(&part.ty, &state.default_types)),
+        );
+        let fields = codegen_all(&self.parts, state);
+
+        let to_xml_fields = codegen_to_xml_fields(&self.parts, state);
+        let from_xml_fields = codegen_from_xml_fields(&self.parts, state);
+        let field_names = field_idents(&self.parts);
+
+        // Single part messages can be built straight from the part they wrap
+        let from_part = match leaf_fields(&self.parts).as_slice() {
+            [part] => {
+                let part_name = member_ident(&part.name.name);
+                let part_ty = codegen_field_ty(&part.ty, &part.name, state);
+
+                quote! {
+                    impl From<#part_ty> for #name {
+                        fn from(value: #part_ty) -> Self {
+                            Self { #part_name: value }
+                        }
+                    }
+                }
+            }
+
+            _ => quote! {},
+        };
+
+        let builder = format_ident!("{}Builder", name);
+        let mut builder_tys = Vec::new();
+        let mut setter_tys = Vec::new();
+        let mut setter_values = Vec::new();
+        let mut build_values = Vec::new();
+
+        for field in leaf_fields(&self.parts) {
+            let field_name = &field.name.name;
+            let ty = codegen_field_ty(&field.ty, &field.name, state);
+
+            // Optional and repeated fields can be left unset, everything else is required
+            match &field.ty {
+                wsdl::FieldKind::Optional(inner) | wsdl::FieldKind::Nillable(inner) => {
+                    builder_tys.push(ty);
+                    setter_tys.push(codegen_field_ty(inner, &field.name, state));
+                    setter_values.push(quote! { Some(value) });
+                    build_values.push(quote! {});
+                }
+
+                wsdl::FieldKind::List(_) => {
+                    builder_tys.push(ty.clone());
+                    setter_tys.push(ty);
+                    setter_values.push(quote! { value });
+                    build_values.push(quote! {});
+                }
+
+                _ => {
+                    builder_tys.push(quote! { Option<#ty> });
+                    setter_tys.push(ty);
+                    setter_values.push(quote! { Some(value) });
+                    build_values.push(quote! {
+                        .ok_or(suds_util::error::BuildError::MissingField(#field_name))?
+                    });
+                }
+            }
+        }
 
         quote! {
-            #[derive(Debug, Clone)]
+            #derives
             pub struct #name {
                 #(#fields)*
             }
@@ -332,10 +1585,40 @@ impl Codegen for wsdl::Message {
             }
 
             impl suds_util::xml::FromXml for #name {
-                fn from_xml<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>) -> Self {
-                    Self {
-                        #(#from_xml_fields)*
+                fn from_xml<R: std::io::BufRead>(reader: &mut suds_util::xml::Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, suds_util::error::XmlError> {
+                    #(#from_xml_fields)*
+
+                    Ok(Self {
+                        #(#field_names,)*
+                    })
+                }
+            }
+
+            #from_part
+
+            impl #name {
+                pub fn builder() -> #builder {
+                    #builder::default()
+                }
+            }
+
+            #[derive(Default)]
+            pub struct #builder {
+                #(#field_names: #builder_tys,)*
+            }
+
+            impl #builder {
+                #(
+                    pub fn #field_names(mut self, value: #setter_tys) -> Self {
+                        self.#field_names = #setter_values;
+                        self
                     }
+                )*
+
+                pub fn build(self) -> Result<#name, suds_util::error::BuildError> {
+                    Ok(#name {
+                        #(#field_names: self.#field_names #build_values,)*
+                    })
                 }
             }
         }
@@ -359,53 +1642,556 @@ impl Codegen for types::Port {
     fn codegen(&self, state: &mut State) -> TokenStream {
         let name = state.rust_name(&self.name);
         let location = &self.location;
+        let port_type = state.rust_name(&self.port_type);
         let operations = codegen_all(&self.operations, state);
 
+        let actions = self
+            .operations
+            .iter()
+            .map(|operation| {
+                let name = soap_action_name(&operation.name.name);
+                let action = &operation.action;
+                quote! { pub const #name: &str = #action; }
+            })
+            .collect::<Vec<_>>();
+
+        let version = match self.version {
+            wsdl::SoapVersion::Soap11 => quote! { suds_util::soap::SoapVersion::Soap11 },
+            wsdl::SoapVersion::Soap12 => quote! { suds_util::soap::SoapVersion::Soap12 },
+        };
+
+        #[cfg(feature = "tls-client-auth")]
+        let with_identity = quote! {
+            pub fn with_identity(identity: suds_util::soap::Identity) -> Result<Self, suds_util::error::SoapError> {
+                Ok(Self {
+                    client: suds_util::soap::Client::with_identity(#location, identity)?.with_version(#version),
+                })
+            }
+        };
+
+        #[cfg(not(feature = "tls-client-auth"))]
+        let with_identity = quote! {};
+
+        let trait_operations = self
+            .operations
+            .iter()
+            .map(|operation| {
+                let signature = codegen_operation_signature(operation, quote! { super::super }, false, false, state);
+                let operation_name = member_ident(&state.rust_name(&operation.name).to_string());
+                let input = operation.input.as_ref().map(|_| quote! { , input });
+
+                quote! {
+                    #signature {
+                        Self::#operation_name(self #input)
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
         quote! {
             pub struct #name {
                 client: suds_util::soap::Client,
             }
 
             impl #name {
+                pub const ENDPOINT: &str = #location;
+                #(#actions)*
+
                 pub fn new() -> Self {
                     Self {
-                        client: suds_util::soap::Client::new(#location),
+                        client: suds_util::soap::Client::new(#location).with_version(#version),
+                    }
+                }
+
+                pub fn with_location(location: &str) -> Self {
+                    Self {
+                        client: suds_util::soap::Client::new(location).with_version(#version),
+                    }
+                }
+
+                pub fn with_client(client: suds_util::soap::Client) -> Self {
+                    Self {
+                        client: client.with_url(#location).with_version(#version),
                     }
                 }
 
+                pub fn with_reqwest(client: suds_util::soap::Reqwest) -> Self {
+                    Self {
+                        client: suds_util::soap::Client::from_reqwest(#location, client).with_version(#version),
+                    }
+                }
+
+                pub fn with_basic_auth(username: &str, password: &str) -> Self {
+                    Self {
+                        client: suds_util::soap::Client::with_basic_auth(#location, username, password).with_version(#version),
+                    }
+                }
+
+                pub fn with_timeout(timeout: std::time::Duration) -> Result<Self, suds_util::error::SoapError> {
+                    Ok(Self {
+                        client: suds_util::soap::Client::with_timeout(#location, timeout)?.with_version(#version),
+                    })
+                }
+
+                pub fn with_proxy(proxy: suds_util::soap::Proxy) -> Result<Self, suds_util::error::SoapError> {
+                    Ok(Self {
+                        client: suds_util::soap::Client::with_proxy(#location, proxy)?.with_version(#version),
+                    })
+                }
+
+                pub fn with_redirect_policy(policy: suds_util::soap::RedirectPolicy) -> Result<Self, suds_util::error::SoapError> {
+                    Ok(Self {
+                        client: suds_util::soap::Client::with_redirect_policy(#location, policy)?.with_version(#version),
+                    })
+                }
+
+                pub fn with_cookies() -> Result<Self, suds_util::error::SoapError> {
+                    Ok(Self {
+                        client: suds_util::soap::Client::with_cookies(#location)?.with_version(#version),
+                    })
+                }
+
+                #with_identity
+
+                pub fn danger_accept_invalid_certs(accept: bool) -> Result<Self, suds_util::error::SoapError> {
+                    Ok(Self {
+                        client: suds_util::soap::Client::danger_accept_invalid_certs(#location, accept)?.with_version(#version),
+                    })
+                }
+
+                pub fn with_security(mut self, security: suds_util::security::SecurityHeader) -> Self {
+                    self.client = self.client.with_security(security);
+                    self
+                }
+
+                pub fn with_validation(mut self, validate: bool) -> Self {
+                    self.client = self.client.with_validation(validate);
+                    self
+                }
+
+                pub fn with_header<K: suds_util::soap::IntoHeaderName>(mut self, name: K, value: suds_util::soap::HeaderValue) -> Self {
+                    self.client = self.client.with_header(name, value);
+                    self
+                }
+
+                pub fn with_headers(mut self, headers: suds_util::soap::HeaderMap) -> Self {
+                    self.client = self.client.with_headers(headers);
+                    self
+                }
+
                 #(#operations)*
             }
+
+            impl super::#port_type for #name {
+                #(#trait_operations)*
+            }
         }
     }
 }
 
-impl Codegen for wsdl::Operation {
+impl Codegen for types::PortType {
     fn codegen(&self, state: &mut State) -> TokenStream {
         let name = state.rust_name(&self.name);
+        let operations = self
+            .operations
+            .iter()
+            .map(|operation| codegen_operation_signature(operation, quote! { super }, false, false, state))
+            .collect::<Vec<_>>();
+
+        // Operations declaring faults get an error type that recognises them in the fault detail
+        let mut errors = Vec::new();
+
+        for operation in &self.operations {
+            if !operation.faults.is_empty() && state.is_new_error(&operation.name) {
+                errors.push(codegen_operation_error(operation, state));
+            }
+        }
 
-        let input = if let Some(input) = &self.input {
-            let ident = state.rust_name(&input);
+        quote! {
+            pub trait #name {
+                #(#operations;)*
+            }
+
+            #(#errors)*
+        }
+    }
+}
+
+fn soap_action_name(operation: &str) -> Ident {
+    format_ident!("{}_SOAP_ACTION", snake_case(operation).to_ascii_uppercase())
+}
+
+fn codegen_operation_signature(
+    operation: &types::Operation,
+    root: TokenStream,
+    convert: bool,
+    headers: bool,
+    state: &mut State,
+) -> TokenStream {
+    let messages = quote! { #root::messages };
+
+    let (name, headers) = if headers {
+        (
+            member_ident(&format!("{}_with_headers", state.rust_name(&operation.name))),
+            quote! { , headers: suds_util::soap::HeaderMap },
+        )
+    } else {
+        (member_ident(&state.rust_name(&operation.name).to_string()), quote! {})
+    };
+
+    let input = if let Some(input) = &operation.input {
+        let ident = state.rust_name(&input);
+
+        if convert {
+            quote! {
+                , input: impl Into<#messages::#ident>
+            }
+        } else {
             quote! {
-                , input: super::super::messages::#ident
+                , input: #messages::#ident
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let output = if let Some(output) = &operation.output {
+        let ident = state.rust_name(&output);
+        quote! {
+            #messages::#ident
+        }
+    } else {
+        quote! { () }
+    };
+
+    let error = if operation.faults.is_empty() {
+        quote! { suds_util::error::SoapError }
+    } else {
+        let ident = state.error_name(&operation.name);
+        quote! { #root::services::#ident }
+    };
+
+    quote! {
+        fn #name(&self #input #headers) -> Result<#output, #error>
+    }
+}
+
+fn codegen_operation_error(operation: &types::Operation, state: &mut State) -> TokenStream {
+    let name = state.error_name(&operation.name);
+
+    let variants = operation
+        .faults
+        .iter()
+        .map(|fault| {
+            let variant = format_ident!("{}", fault.name);
+            let message = state.rust_name(&fault.message);
+            (fault.name.as_str(), variant, message)
+        })
+        .collect::<Vec<_>>();
+
+    let declarations = variants.iter().map(|(_, variant, message)| {
+        quote! { #variant(super::messages::#message) }
+    });
+
+    let conversions = variants.iter().map(|(_, variant, message)| {
+        quote! {
+            if let Some(detail) = fault.detail_as::<super::messages::#message>() {
+                return Self::#variant(detail);
             }
+        }
+    });
+
+    let displays = variants.iter().map(|(fault, variant, _)| {
+        quote! { Self::#variant(_) => write!(f, "{} fault", #fault) }
+    });
+
+    quote! {
+        #[derive(Debug)]
+        pub enum #name {
+            #(#declarations,)*
+            Fault(suds_util::error::SoapError),
+        }
+
+        impl From<suds_util::error::SoapError> for #name {
+            fn from(error: suds_util::error::SoapError) -> Self {
+                if let suds_util::error::SoapError::Fault(fault) = &error {
+                    #(#conversions)*
+                }
+
+                Self::Fault(error)
+            }
+        }
+
+        impl std::fmt::Display for #name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    #(#displays,)*
+                    Self::Fault(error) => write!(f, "{}", error),
+                }
+            }
+        }
+
+        impl std::error::Error for #name {}
+    }
+}
+
+impl Codegen for types::Operation {
+    fn codegen(&self, state: &mut State) -> TokenStream {
+        let signature = codegen_operation_signature(self, quote! { super::super }, true, false, state);
+        let headers_signature = codegen_operation_signature(self, quote! { super::super }, true, true, state);
+        let action = soap_action_name(&self.name.name);
+
+        // Operations without an input still send an envelope, just with an empty body
+        let body = if self.input.is_some() {
+            quote! { input.into() }
         } else {
-            quote! {}
+            quote! { () }
         };
 
-        let output = if let Some(output) = &self.output {
-            let ident = state.rust_name(&output);
+        // Encoded bodies aren't supported, so they are sent as their literal equivalent
+        let envelope = if self.style == "rpc" {
+            let operation_name = &self.name.name;
+
+            // Without an explicit soap:body namespace the wrapper is qualified by the target namespace
+            let namespace = self.namespace.clone().unwrap_or_else(|| state.namespace(&self.name));
+
             quote! {
-                -> super::super::messages::#ident
+                let envelope = suds_util::soap::Envelope::new(suds_util::soap::RpcBody::new(#operation_name, #namespace, #body));
             }
         } else {
-            quote! {}
+            quote! {
+                let envelope = suds_util::soap::Envelope::new(#body);
+            }
         };
 
+        // One-way operations get no response to read back
+        let (typed, typed_with_headers) = if self.output.is_none() {
+            (
+                quote! {
+                    self.client.send_one_way(Self::#action, envelope)?;
+                    Ok(())
+                },
+                quote! {
+                    self.client.send_one_way_with_headers(Self::#action, envelope, headers)?;
+                    Ok(())
+                },
+            )
+        } else if self.style == "rpc" {
+            (
+                quote! {
+                    let response: suds_util::soap::Envelope<suds_util::soap::RpcBody<_>> = self.client.send(Self::#action, envelope)?;
+                    Ok(response.into_body().into_inner())
+                },
+                quote! {
+                    let response: suds_util::soap::Envelope<suds_util::soap::RpcBody<_>> = self.client.send_with_headers(Self::#action, envelope, headers)?;
+                    Ok(response.into_body().into_inner())
+                },
+            )
+        } else {
+            (
+                quote! {
+                    Ok(self.client.send(Self::#action, envelope)?.into_body())
+                },
+                quote! {
+                    Ok(self.client.send_with_headers(Self::#action, envelope, headers)?.into_body())
+                },
+            )
+        };
+
+        #[cfg(feature = "raw-operations")]
+        let raw = {
+            let name = member_ident(&format!("{}_raw", state.rust_name(&self.name)));
+            let input = self.input.as_ref().map(|input| {
+                let ident = state.rust_name(input);
+                quote! { , input: impl Into<super::super::messages::#ident> }
+            });
+
+            quote! {
+                pub fn #name(&self #input) -> Result<String, suds_util::error::SoapError> {
+                    #envelope
+                    self.client.send_raw(Self::#action, envelope)
+                }
+            }
+        };
+
+        #[cfg(not(feature = "raw-operations"))]
+        let raw = quote! {};
+
         quote! {
-            pub fn #name(&self #input) #output {
-                let envelope = suds_util::soap::Envelope::new(input);
-                self.client.send(envelope).into_body()
+            pub #signature {
+                #envelope
+                #typed
+            }
+
+            pub #headers_signature {
+                #envelope
+                #typed_with_headers
             }
+
+            #raw
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn output_does_not_depend_on_import_order() {
+        let directory = std::env::temp_dir().join(format!("suds-order-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        for name in ["a", "b"] {
+            let schema = format!(
+                r#"<xsd:schema xmlns:xsd="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:{}">
+                    <xsd:complexType name="Item">
+                        <xsd:sequence>
+                            <xsd:element name="{}" type="xsd:string"/>
+                        </xsd:sequence>
+                    </xsd:complexType>
+                </xsd:schema>"#,
+                name, name
+            );
+
+            std::fs::write(directory.join(format!("{}.xsd", name)), schema).unwrap();
         }
+
+        let base = directory.to_string_lossy().into_owned();
+        let generate = |first: &str, second: &str| {
+            let wsdl = format!(
+                r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/"
+                                xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+                                targetNamespace="http://example.com/test">
+                    <types>
+                        <xsd:schema targetNamespace="http://example.com/test">
+                            <xsd:import namespace="urn:{}" schemaLocation="{}.xsd"/>
+                            <xsd:import namespace="urn:{}" schemaLocation="{}.xsd"/>
+                        </xsd:schema>
+                    </types>
+                </definitions>"#,
+                first, first, second, second
+            );
+
+            crate::from_str(&wsdl, &base).unwrap().to_string()
+        };
+
+        let forwards = generate("a", "b");
+        let backwards = generate("b", "a");
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(forwards, backwards);
+    }
+
+    #[test]
+    fn documentation_is_trimmed_into_a_doc_attribute() {
+        let doc = super::codegen_doc(&Some(" The three letter station code ".to_owned()));
+        assert_eq!(doc.to_string(), quote::quote! { #[doc = "The three letter station code"] }.to_string());
+
+        assert!(super::codegen_doc(&None).is_empty());
+    }
+
+    #[test]
+    fn parsed_definitions_can_be_preprocessed() {
+        let path = std::env::temp_dir().join(format!("suds-parse-{}.wsdl", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/"
+                            xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+                            xmlns:tns="http://example.com/test"
+                            targetNamespace="http://example.com/test">
+                <portType name="PingPortType">
+                    <operation name="Ping"/>
+                </portType>
+                <binding name="PingBinding" type="tns:PingPortType">
+                    <soap:binding style="document" transport="http://schemas.xmlsoap.org/soap/http"/>
+                    <operation name="Ping">
+                        <soap:operation soapAction="http://example.com/test/Ping" style="document"/>
+                    </operation>
+                </binding>
+                <service name="PingService">
+                    <port name="PingPort" binding="tns:PingBinding">
+                        <soap:address location="http://example.com/ping"/>
+                    </port>
+                </service>
+            </definitions>"#,
+        )
+        .unwrap();
+
+        let parsed = crate::parse(path.to_string_lossy());
+        std::fs::remove_file(&path).unwrap();
+
+        let (definition, namespaces) = parsed.unwrap();
+        let preprocessed = crate::preprocessed(&definition);
+
+        assert_eq!(preprocessed.services[0].ports[0].location, "http://example.com/ping");
+        assert_eq!(preprocessed.port_types[0].operations[0].action, "http://example.com/test/Ping");
+        assert!(namespaces.namespaces().iter().any(|namespace| namespace == "http://example.com/test"));
+    }
+
+    #[test]
+    fn ports_keep_their_soap_version() {
+        let path = std::env::temp_dir().join(format!("suds-versions-{}.wsdl", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/"
+                            xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+                            xmlns:soap12="http://schemas.xmlsoap.org/wsdl/soap12/"
+                            xmlns:tns="http://example.com/test"
+                            targetNamespace="http://example.com/test">
+                <portType name="PingPortType">
+                    <operation name="Ping"/>
+                </portType>
+                <binding name="PingSoap" type="tns:PingPortType">
+                    <soap:binding style="document" transport="http://schemas.xmlsoap.org/soap/http"/>
+                    <operation name="Ping">
+                        <soap:operation soapAction="http://example.com/test/Ping" style="document"/>
+                    </operation>
+                </binding>
+                <binding name="PingSoap12" type="tns:PingPortType">
+                    <soap12:binding style="document" transport="http://schemas.xmlsoap.org/soap/http"/>
+                    <operation name="Ping">
+                        <soap12:operation soapAction="http://example.com/test/Ping" style="document"/>
+                    </operation>
+                </binding>
+                <service name="PingService">
+                    <port name="PingSoap" binding="tns:PingSoap">
+                        <soap:address location="http://example.com/ping"/>
+                    </port>
+                    <port name="PingSoap12" binding="tns:PingSoap12">
+                        <soap12:address location="http://example.com/ping12"/>
+                    </port>
+                </service>
+            </definitions>"#,
+        )
+        .unwrap();
+
+        let parsed = crate::parse(path.to_string_lossy());
+        std::fs::remove_file(&path).unwrap();
+
+        let preprocessed = crate::preprocessed(&parsed.unwrap().0);
+        let ports = preprocessed.services[0]
+            .ports
+            .iter()
+            .map(|port| (port.name.name.as_str(), port.version))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            ports,
+            [
+                ("PingSoap", suds_wsdl::types::SoapVersion::Soap11),
+                ("PingSoap12", suds_wsdl::types::SoapVersion::Soap12)
+            ]
+        );
+    }
+
+    #[test]
+    fn files_resolve_imports_against_their_directory() {
+        let directory = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+        let wsdl = std::fs::read_to_string(directory.join("ping.wsdl")).unwrap();
+        let base = suds_wsdl::Url::from_directory_path(&directory).unwrap();
+
+        assert_eq!(
+            crate::from_file("fixtures/ping.wsdl").unwrap().to_string(),
+            crate::from_str(&wsdl, base.as_str()).unwrap().to_string()
+        );
     }
 }
@@ -0,0 +1,39 @@
+use proc_macro2::TokenStream;
+use suds_wsdl::error;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FormatError {
+    #[error("Error parsing WSDL")]
+    ParseError(#[from] error::Error),
+
+    #[error("Error formatting generated code")]
+    SynError(#[from] syn::Error),
+}
+
+pub fn pretty(tokens: TokenStream) -> Result<String, syn::Error> {
+    let file: syn::File = syn::parse2(tokens)?;
+    Ok(prettyplease::unparse(&file))
+}
+
+pub fn to_string<S: AsRef<str>>(url: S) -> Result<String, FormatError> {
+    Ok(pretty(super::from_url(url)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn formatting_is_stable() {
+        let formatted = to_string("fixtures/ping.wsdl").unwrap();
+        assert_eq!(pretty(formatted.parse().unwrap()).unwrap(), formatted);
+    }
+
+    #[test]
+    fn errors_are_surfaced() {
+        assert!(matches!(to_string("fixtures/missing.wsdl"), Err(FormatError::ParseError(_))));
+        assert!(pretty(quote! { pub mod }).is_err());
+    }
+}
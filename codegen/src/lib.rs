@@ -1,22 +1,62 @@
+#![deny(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)]
+
 use proc_macro2::TokenStream;
+use std::path::Path;
 use suds_wsdl::{
     self as wsdl, error,
     types::{Definition, Namespaces},
 };
 
 mod codegen;
+mod format;
 mod preprocessor;
-mod types;
+
+#[cfg(test)]
+mod snapshots;
+
+pub mod types;
+
+pub use codegen::snake_case;
+pub use format::{pretty, to_string, FormatError};
+pub use wsdl::{parse, resolver};
 
 pub fn from_url<S: AsRef<str>>(url: S) -> Result<TokenStream, error::Error> {
     let (definition, namespaces) = wsdl::parse(url)?;
     from_definition(&definition, &namespaces)
 }
 
+// Imports with relative locations are resolved against the directory containing the file
+pub fn from_file<P: AsRef<Path>>(path: P) -> Result<TokenStream, error::Error> {
+    let path = path
+        .as_ref()
+        .canonicalize()
+        .map_err(|err| error::Error::PathConversionError(Some(err)))?;
+
+    let url = wsdl::Url::from_file_path(&path).map_err(|()| error::Error::PathConversionError(None))?;
+    from_url(url)
+}
+
+pub fn from_url_with_resolver<S: AsRef<str>>(
+    url: S,
+    resolver: &dyn resolver::Resolver,
+) -> Result<TokenStream, error::Error> {
+    let (definition, namespaces) = wsdl::parse_with_resolver(url, resolver)?;
+    from_definition(&definition, &namespaces)
+}
+
+pub fn from_str(wsdl: &str, base_url: &str) -> Result<TokenStream, error::Error> {
+    let (definition, namespaces) = wsdl::parse_str(wsdl, wsdl::to_url(base_url)?)?;
+    from_definition(&definition, &namespaces)
+}
+
+pub fn preprocessed(definition: &Definition) -> types::Definition {
+    preprocessor::preprocess(definition)
+}
+
 pub fn from_definition(
     definition: &Definition,
     namespaces: &Namespaces,
 ) -> Result<TokenStream, error::Error> {
-    let definition = preprocessor::preprocess(definition);
+    let definition = preprocessed(definition);
     Ok(codegen::codegen(&definition, namespaces))
 }
@@ -3,6 +3,7 @@ use suds_wsdl::types as wsdl;
 
 pub fn preprocess(definition: &wsdl::Definition) -> types::Definition {
     let mut services = Vec::new();
+    let mut port_types = Vec::<types::PortType>::new();
 
     for service in &definition.services {
         let mut ports = Vec::new();
@@ -28,10 +29,45 @@ pub fn preprocess(definition: &wsdl::Definition) -> types::Definition {
                 unimplemented!()
             };
 
+            let operations = port_type
+                .operations
+                .iter()
+                .map(|operation| {
+                    let binding_operation = binding
+                        .operations
+                        .iter()
+                        .find(|binding_operation| binding_operation.name == operation.name);
+
+                    types::Operation {
+                        name: operation.name.clone(),
+                        input: operation.input.clone(),
+                        output: operation.output.clone(),
+                        action: binding_operation
+                            .map(|binding_operation| binding_operation.action.clone())
+                            .unwrap_or_default(),
+                        style: binding_operation
+                            .map(|binding_operation| binding_operation.style.clone())
+                            .unwrap_or_else(|| "document".to_owned()),
+                        body_use: binding_operation.and_then(|binding_operation| binding_operation.input.clone()),
+                        namespace: binding_operation.and_then(|binding_operation| binding_operation.namespace.clone()),
+                        faults: operation.faults.clone(),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            if !port_types.iter().any(|existing| existing.name == port_type.name) {
+                port_types.push(types::PortType {
+                    name: port_type.name.clone(),
+                    operations: operations.clone(),
+                });
+            }
+
             ports.push(types::Port {
                 name: port.name.clone(),
                 location: port.location.clone(),
-                operations: port_type.operations.clone(),
+                port_type: port_type.name.clone(),
+                version: binding.version,
+                operations,
             });
         }
 
@@ -43,7 +79,60 @@ pub fn preprocess(definition: &wsdl::Definition) -> types::Definition {
 
     types::Definition {
         services,
+        port_types,
         messages: definition.messages.clone(),
         types: definition.types.clone(),
+        substitution_groups: definition.substitution_groups.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::preprocess;
+
+    fn parse(name: &str, body: &str) -> suds_wsdl::types::Definition {
+        let path = std::env::temp_dir().join(format!("suds-{}-{}.wsdl", name, std::process::id()));
+
+        std::fs::write(
+            &path,
+            format!(
+                r#"<?xml version="1.0"?>
+                <definitions xmlns="http://schemas.xmlsoap.org/wsdl/"
+                             xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+                             xmlns:tns="http://example.com/test"
+                             targetNamespace="http://example.com/test">{}</definitions>"#,
+                body
+            ),
+        )
+        .unwrap();
+
+        suds_wsdl::parse(path.to_str().unwrap()).unwrap().0
+    }
+
+    #[test]
+    fn operations_take_their_action_from_the_binding() {
+        let definition = parse(
+            "action",
+            r#"<portType name="PingPortType">
+                <operation name="Ping"/>
+            </portType>
+            <binding name="PingBinding" type="tns:PingPortType">
+                <soap:binding style="document" transport="http://schemas.xmlsoap.org/soap/http"/>
+                <operation name="Ping">
+                    <soap:operation soapAction="http://example.com/test/Ping" style="document"/>
+                </operation>
+            </binding>
+            <service name="PingService">
+                <port name="PingPort" binding="tns:PingBinding">
+                    <soap:address location="http://example.com/ping"/>
+                </port>
+            </service>"#,
+        );
+
+        let definition = preprocess(&definition);
+        let operation = &definition.services[0].ports[0].operations[0];
+
+        assert_eq!(operation.name.name, "Ping");
+        assert_eq!(operation.action, "http://example.com/test/Ping");
     }
 }
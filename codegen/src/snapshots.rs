@@ -0,0 +1,184 @@
+use proc_macro2::TokenStream;
+use std::{env, fs, path::PathBuf};
+
+// Features that change the generated code, each of which may have its own snapshot variant
+const FEATURES: &[(&str, bool)] = &[
+    ("raw-operations", cfg!(feature = "raw-operations")),
+    ("snake-case", cfg!(feature = "snake-case")),
+    ("tls-client-auth", cfg!(feature = "tls-client-auth")),
+];
+
+fn read(path: &PathBuf) -> Option<String> {
+    let snapshot = fs::read_to_string(path).ok()?;
+    Some(crate::pretty(snapshot.parse().unwrap()).unwrap())
+}
+
+// Rerun with SUDS_UPDATE_SNAPSHOTS=1 to overwrite the committed snapshots with the current output
+fn check(name: &str, tokens: TokenStream) {
+    let directory = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("snapshots");
+    let base = directory.join(format!("{}.rs", name));
+
+    let features = FEATURES
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(feature, _)| format!(".{}", feature))
+        .collect::<String>();
+    let variant = directory.join(format!("{}{}.rs", name, features));
+
+    let generated = crate::pretty(tokens).unwrap();
+
+    if env::var_os("SUDS_UPDATE_SNAPSHOTS").is_some() {
+        // Variants are only kept where they differ from the default output
+        if variant == base || read(&base).as_ref() != Some(&generated) {
+            fs::write(&variant, &generated).unwrap();
+        } else if variant.exists() {
+            fs::remove_file(&variant).unwrap();
+        }
+
+        return;
+    }
+
+    let snapshot = read(&variant)
+        .or_else(|| read(&base))
+        .unwrap_or_else(|| panic!("no snapshot at {}", base.display()));
+
+    assert_eq!(generated, snapshot, "generated code differs from {}", variant.display());
+}
+
+fn schema(types: &str) -> TokenStream {
+    let wsdl = format!(
+        r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/"
+                        xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+                        xmlns:tns="http://example.com/test"
+                        targetNamespace="http://example.com/test">
+            <types>
+                <xsd:schema targetNamespace="http://example.com/test">{}</xsd:schema>
+            </types>
+        </definitions>"#,
+        types
+    );
+
+    crate::from_str(&wsdl, "http://example.com/").unwrap()
+}
+
+#[test]
+fn struct_type() {
+    check(
+        "struct",
+        schema(
+            r#"<xsd:complexType name="Point">
+                <xsd:sequence>
+                    <xsd:element name="x" type="xsd:int"/>
+                    <xsd:element name="label" type="xsd:string" minOccurs="0"/>
+                </xsd:sequence>
+            </xsd:complexType>"#,
+        ),
+    );
+}
+
+#[test]
+fn member_names() {
+    check(
+        "member_names",
+        schema(
+            r#"<xsd:complexType name="Reading">
+                <xsd:sequence>
+                    <xsd:element name="sensorID" type="xsd:string"/>
+                    <xsd:element name="maxValue" type="xsd:int"/>
+                </xsd:sequence>
+                <xsd:attribute name="unitName" type="xsd:string"/>
+            </xsd:complexType>"#,
+        ),
+    );
+}
+
+#[test]
+fn simple_type() {
+    check(
+        "simple_type",
+        schema(
+            r#"<xsd:simpleType name="Code">
+                <xsd:restriction base="xsd:string">
+                    <xsd:maxLength value="3"/>
+                </xsd:restriction>
+            </xsd:simpleType>"#,
+        ),
+    );
+}
+
+// Bounds become literals of the base type, and ones that don't fit it fail the build
+#[test]
+fn restrictions() {
+    check(
+        "restrictions",
+        schema(
+            r#"<xsd:simpleType name="Percentage">
+                <xsd:restriction base="xsd:int">
+                    <xsd:minInclusive value="-5"/>
+                    <xsd:maxInclusive value="+10"/>
+                </xsd:restriction>
+            </xsd:simpleType>
+            <xsd:simpleType name="Ratio">
+                <xsd:restriction base="xsd:double">
+                    <xsd:maxInclusive value="2.5"/>
+                </xsd:restriction>
+            </xsd:simpleType>
+            <xsd:simpleType name="Letters">
+                <xsd:restriction base="xsd:string">
+                    <xsd:pattern value="[a-z]+"/>
+                </xsd:restriction>
+            </xsd:simpleType>
+            <xsd:simpleType name="Low">
+                <xsd:restriction base="xsd:int">
+                    <xsd:minInclusive value="low"/>
+                </xsd:restriction>
+            </xsd:simpleType>
+            <xsd:simpleType name="Overflow">
+                <xsd:restriction base="xsd:unsignedByte">
+                    <xsd:maxInclusive value="256"/>
+                </xsd:restriction>
+            </xsd:simpleType>
+            <xsd:simpleType name="Named">
+                <xsd:restriction base="xsd:string">
+                    <xsd:maxInclusive value="z"/>
+                </xsd:restriction>
+            </xsd:simpleType>"#,
+        ),
+    );
+}
+
+#[test]
+fn unsupported_inline_type() {
+    check(
+        "unsupported_inline",
+        schema(
+            r#"<xsd:complexType name="Order">
+                <xsd:sequence>
+                    <xsd:element name="status">
+                        <xsd:complexType>
+                            <xsd:choice>
+                                <xsd:element name="open" type="xsd:string"/>
+                                <xsd:element name="closed" type="xsd:int"/>
+                            </xsd:choice>
+                        </xsd:complexType>
+                    </xsd:element>
+                </xsd:sequence>
+            </xsd:complexType>"#,
+        ),
+    );
+}
+
+#[test]
+fn self_alias() {
+    check("self_alias", schema(r#"<xsd:element name="Loop" type="tns:Loop"/>"#));
+}
+
+#[test]
+fn service() {
+    check("service", crate::from_file("fixtures/ping.wsdl").unwrap());
+}
+
+#[test]
+fn declared_faults() {
+    check("faults", crate::from_file("fixtures/faults.wsdl").unwrap());
+}
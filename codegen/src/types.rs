@@ -10,12 +10,34 @@ pub struct Service {
 pub struct Port {
     pub name: NamespacedName,
     pub location: String,
-    pub operations: Vec<wsdl::Operation>,
+    pub port_type: NamespacedName,
+    pub version: wsdl::SoapVersion,
+    pub operations: Vec<Operation>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PortType {
+    pub name: NamespacedName,
+    pub operations: Vec<Operation>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Operation {
+    pub name: NamespacedName,
+    pub input: Option<NamespacedName>,
+    pub output: Option<NamespacedName>,
+    pub action: String,
+    pub style: String,
+    pub body_use: Option<String>,
+    pub namespace: Option<String>,
+    pub faults: Vec<wsdl::Fault>,
 }
 
 #[derive(Default, Debug, Clone)]
 pub struct Definition {
     pub services: Vec<Service>,
+    pub port_types: Vec<PortType>,
     pub messages: Vec<wsdl::Message>,
     pub types: Vec<wsdl::Type>,
+    pub substitution_groups: Vec<wsdl::SubstitutionGroup>,
 }
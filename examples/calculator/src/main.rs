@@ -1,9 +1,8 @@
 use structopt::StructOpt;
+use suds_macro::suds;
+use suds_util::error::SoapError;
 
-mod calculator {
-    use suds_macro::suds;
-    suds! {"http://www.dneonline.com/calculator.asmx?WSDL"}
-}
+suds! {mod calculator = "http://www.dneonline.com/calculator.asmx?WSDL"}
 
 pub struct Calculator {
     client: calculator::services::Calculator::CalculatorSoap,
@@ -16,36 +15,28 @@ impl Calculator {
         }
     }
 
-    pub fn add(&self, a: isize, b: isize) -> isize {
-        let result = self.client.Add(calculator::messages::AddSoapIn {
-            parameters: calculator::types::Add { intA: a, intB: b },
-        });
+    pub fn add(&self, a: i32, b: i32) -> Result<i32, SoapError> {
+        let result = self.client.Add(calculator::types::Add { intA: a, intB: b })?;
 
-        result.parameters.AddResult
+        Ok(result.parameters.AddResult)
     }
 
-    pub fn subtract(&self, a: isize, b: isize) -> isize {
-        let result = self.client.Subtract(calculator::messages::SubtractSoapIn {
-            parameters: calculator::types::Subtract { intA: a, intB: b },
-        });
+    pub fn subtract(&self, a: i32, b: i32) -> Result<i32, SoapError> {
+        let result = self.client.Subtract(calculator::types::Subtract { intA: a, intB: b })?;
 
-        result.parameters.SubtractResult
+        Ok(result.parameters.SubtractResult)
     }
 
-    pub fn multiply(&self, a: isize, b: isize) -> isize {
-        let result = self.client.Multiply(calculator::messages::MultiplySoapIn {
-            parameters: calculator::types::Multiply { intA: a, intB: b },
-        });
+    pub fn multiply(&self, a: i32, b: i32) -> Result<i32, SoapError> {
+        let result = self.client.Multiply(calculator::types::Multiply { intA: a, intB: b })?;
 
-        result.parameters.MultiplyResult
+        Ok(result.parameters.MultiplyResult)
     }
 
-    pub fn divide(&self, a: isize, b: isize) -> isize {
-        let result = self.client.Divide(calculator::messages::DivideSoapIn {
-            parameters: calculator::types::Divide { intA: a, intB: b },
-        });
+    pub fn divide(&self, a: i32, b: i32) -> Result<i32, SoapError> {
+        let result = self.client.Divide(calculator::types::Divide { intA: a, intB: b })?;
 
-        result.parameters.DivideResult
+        Ok(result.parameters.DivideResult)
     }
 }
 
@@ -62,19 +53,19 @@ struct Args {
     #[structopt(subcommand)]
     mode: Mode,
 
-    a: isize,
-    b: isize,
+    a: i32,
+    b: i32,
 }
 
 #[paw::main]
-fn main(args: Args) -> Result<(), std::io::Error> {
+fn main(args: Args) -> Result<(), SoapError> {
     let calculator = Calculator::new();
 
     let result = match args.mode {
-        Mode::Add => calculator.add(args.a, args.b),
-        Mode::Subtract => calculator.subtract(args.a, args.b),
-        Mode::Multiply => calculator.multiply(args.a, args.b),
-        Mode::Divide => calculator.divide(args.a, args.b),
+        Mode::Add => calculator.add(args.a, args.b)?,
+        Mode::Subtract => calculator.subtract(args.a, args.b)?,
+        Mode::Multiply => calculator.multiply(args.a, args.b)?,
+        Mode::Divide => calculator.divide(args.a, args.b)?,
     };
 
     println!("{}", result);
@@ -1,11 +1,151 @@
+#![deny(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)]
+
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use std::{fmt::Display, path::Path};
 use suds_codegen as codegen;
-use syn::{parse_macro_input, LitStr};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    Ident, ItemMod, LitStr, Token,
+};
+
+struct Input {
+    module: Option<Ident>,
+    url: LitStr,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let module = if input.peek(Token![mod]) {
+            input.parse::<Token![mod]>()?;
+            let module = input.parse()?;
+            input.parse::<Token![=]>()?;
+            Some(module)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            module,
+            url: input.parse()?,
+        })
+    }
+}
+
+fn wrap(module: Option<Ident>, tokens: proc_macro2::TokenStream) -> TokenStream {
+    match module {
+        Some(module) => quote! {
+            pub mod #module {
+                #tokens
+            }
+        }
+        .into(),
+        None => tokens.into(),
+    }
+}
+
+// Parse and generation errors are reported against the given source rather than panicking
+fn generated<E: Display>(
+    source: &LitStr,
+    result: Result<proc_macro2::TokenStream, E>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    result.map_err(|error| syn::Error::new_spanned(source, error))
+}
 
 #[proc_macro]
 pub fn suds(input: TokenStream) -> TokenStream {
-    let s = parse_macro_input!(input as LitStr);
-    codegen::from_url(s.value()).unwrap().into()
+    let input = parse_macro_input!(input as Input);
+
+    match generated(&input.url, codegen::from_url(input.url.value())) {
+        Ok(tokens) => wrap(input.module, tokens),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+// Fills an empty module with the generated items, so it is declared like any other module
+#[proc_macro_attribute]
+pub fn suds_module(args: TokenStream, item: TokenStream) -> TokenStream {
+    let url = parse_macro_input!(args as LitStr);
+    let module = parse_macro_input!(item as ItemMod);
+
+    if let Some((_, items)) = &module.content {
+        if !items.is_empty() {
+            return syn::Error::new_spanned(&module, "Expected an empty module")
+                .to_compile_error()
+                .into();
+        }
+    }
+
+    let ItemMod { attrs, vis, ident, .. } = module;
+    let tokens = match generated(&url, codegen::from_url(url.value())) {
+        Ok(tokens) => tokens,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    quote! {
+        #(#attrs)*
+        #vis mod #ident {
+            #tokens
+        }
+    }
+    .into()
+}
+
+#[proc_macro]
+pub fn suds_file(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Input);
+    let path = Path::new(&std::env::var("CARGO_MANIFEST_DIR").unwrap()).join(input.url.value());
+
+    match generated(&input.url, codegen::from_url(path.to_string_lossy())) {
+        Ok(tokens) => wrap(input.module, tokens),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+pub fn suds_str(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input with Punctuated::<LitStr, Token![,]>::parse_terminated);
+
+    match expand_str(args) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand_str(args: Punctuated<LitStr, Token![,]>) -> syn::Result<proc_macro2::TokenStream> {
+    let mut args = args.into_iter();
+
+    let wsdl = args.next().ok_or_else(|| syn::Error::new(Span::call_site(), "Expected WSDL source"))?;
+    let base = args
+        .next()
+        .map(|base| base.value())
+        .unwrap_or_else(|| std::env::var("CARGO_MANIFEST_DIR").unwrap());
+
+    generated(&wsdl, codegen::from_str(&wsdl.value(), &base))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(args: &str) -> syn::Result<proc_macro2::TokenStream> {
+        expand_str(syn::parse::Parser::parse_str(Punctuated::parse_terminated, args).unwrap())
+    }
+
+    #[test]
+    fn missing_source_is_an_error() {
+        let error = expand("").unwrap_err();
+        assert_eq!(error.to_string(), "Expected WSDL source");
+    }
+
+    #[test]
+    fn generation_errors_are_returned() {
+        let error = expand(r#""<definitions targetNamespace=\"urn:test\"/>", "not a url""#).unwrap_err();
+        assert_eq!(error.to_string(), "Unable to convert provided path");
+    }
 }
@@ -0,0 +1,25 @@
+#![cfg(feature = "chrono")]
+
+mod common;
+
+use common::{from_str, to_string};
+use suds_util::chrono::{DateTime, NaiveDate};
+
+#[allow(dead_code)]
+mod generated {
+    suds_macro::suds_file!("tests/chrono.wsdl");
+}
+
+use generated::types;
+
+#[test]
+fn date_times_round_trip() {
+    let timetable = types::Timetable {
+        departs: DateTime::parse_from_rfc3339("2021-11-05T09:30:00+01:00").unwrap(),
+        day: NaiveDate::from_ymd_opt(2021, 11, 5).unwrap(),
+    };
+
+    let xml = to_string(&timetable);
+    assert!(xml.contains(">2021-11-05T09:30:00+01:00<"));
+    assert_eq!(from_str::<types::Timetable>(&xml), timetable);
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::{from_str, to_string};
+
+#[allow(dead_code)]
+mod generated {
+    suds_macro::suds_str!(
+        r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/"
+                        xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+                        targetNamespace="http://example.com/collisions">
+            <types>
+                <xsd:schema targetNamespace="urn:first">
+                    <xsd:complexType name="Item">
+                        <xsd:sequence>
+                            <xsd:element name="name" type="xsd:string"/>
+                        </xsd:sequence>
+                    </xsd:complexType>
+                </xsd:schema>
+
+                <xsd:schema targetNamespace="urn:second">
+                    <xsd:complexType name="Item">
+                        <xsd:sequence>
+                            <xsd:element name="count" type="xsd:int"/>
+                        </xsd:sequence>
+                    </xsd:complexType>
+
+                    <xsd:complexType name="Unique">
+                        <xsd:sequence>
+                            <xsd:element name="value" type="xsd:int"/>
+                        </xsd:sequence>
+                    </xsd:complexType>
+                </xsd:schema>
+            </types>
+        </definitions>"#,
+        "http://example.com/"
+    );
+}
+
+use generated::types;
+
+#[test]
+fn colliding_names_are_suffixed_by_namespace() {
+    let first = types::ItemNs1 {
+        name: "first".to_owned(),
+    };
+    let second = types::ItemNs2 { count: 2 };
+
+    assert_eq!(from_str::<types::ItemNs1>(&to_string(&first)), first);
+    assert_eq!(from_str::<types::ItemNs2>(&to_string(&second)), second);
+
+    // Names without a collision are left alone
+    let _ = types::Unique { value: 1 };
+}
@@ -0,0 +1,74 @@
+#![allow(dead_code)]
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use suds_util::{
+    error::XmlError,
+    xml::{FromXml, Reader, ToXml, Writer},
+};
+
+pub fn to_string<T: ToXml>(value: &T) -> String {
+    let mut writer = Writer::new(Vec::new());
+    value.to_xml(&mut writer, true);
+    String::from_utf8(writer.into_inner()).unwrap()
+}
+
+pub fn from_str<T: FromXml>(xml: &str) -> T {
+    try_from_str(xml).unwrap()
+}
+
+pub fn try_from_str<T: FromXml>(xml: &str) -> Result<T, XmlError> {
+    let mut reader = Reader::from_reader(xml.as_bytes());
+    reader.trim_text(true);
+    reader.expand_empty_elements(true);
+
+    T::from_xml(&mut reader, &mut Vec::new())
+}
+
+// Answers a single request with the given envelope body, passing the request body back to the test
+pub fn mock_server(body: &str) -> (&'static str, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let response = format!("<Envelope><Body>{}</Body></Envelope>", body);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut length = 0;
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+
+            match line.trim_end().split_once(':') {
+                Some((key, value)) if key.eq_ignore_ascii_case("content-length") => length = value.trim().parse().unwrap(),
+                Some(_) => (),
+                None => break,
+            }
+        }
+
+        let mut request = vec![0; length];
+        reader.read_exact(&mut request).unwrap();
+
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            response.len(),
+            response
+        )
+        .unwrap();
+
+        sender.send(String::from_utf8(request).unwrap()).unwrap();
+    });
+
+    (Box::leak(url.into_boxed_str()), receiver)
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use common::mock_server;
+use suds_util::error::SoapError;
+
+#[allow(dead_code, non_camel_case_types, non_snake_case)]
+mod generated {
+    suds_macro::suds_str!(
+        r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/"
+                        xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+                        xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+                        xmlns:tns="http://example.com/faults"
+                        targetNamespace="http://example.com/faults">
+            <types>
+                <xsd:schema targetNamespace="http://example.com/faults">
+                    <xsd:element name="Ping">
+                        <xsd:complexType>
+                            <xsd:sequence>
+                                <xsd:element name="value" type="xsd:string"/>
+                            </xsd:sequence>
+                        </xsd:complexType>
+                    </xsd:element>
+
+                    <xsd:element name="PingResponse">
+                        <xsd:complexType>
+                            <xsd:sequence>
+                                <xsd:element name="result" type="xsd:string"/>
+                            </xsd:sequence>
+                        </xsd:complexType>
+                    </xsd:element>
+
+                    <xsd:element name="InvalidPing">
+                        <xsd:complexType>
+                            <xsd:sequence>
+                                <xsd:element name="reason" type="xsd:string"/>
+                            </xsd:sequence>
+                        </xsd:complexType>
+                    </xsd:element>
+                </xsd:schema>
+            </types>
+
+            <message name="PingRequest">
+                <part name="parameters" element="tns:Ping"/>
+            </message>
+
+            <message name="PingResponse">
+                <part name="parameters" element="tns:PingResponse"/>
+            </message>
+
+            <message name="InvalidPingFault">
+                <part name="fault" element="tns:InvalidPing"/>
+            </message>
+
+            <portType name="PingPortType">
+                <operation name="ping">
+                    <input message="tns:PingRequest"/>
+                    <output message="tns:PingResponse"/>
+                    <fault name="InvalidPing" message="tns:InvalidPingFault"/>
+                </operation>
+            </portType>
+
+            <binding name="PingBinding" type="tns:PingPortType">
+                <soap:binding style="document" transport="http://schemas.xmlsoap.org/soap/http"/>
+                <operation name="ping">
+                    <soap:operation soapAction="http://example.com/faults/Ping" style="document"/>
+                    <input><soap:body use="literal"/></input>
+                    <output><soap:body use="literal"/></output>
+                    <fault name="InvalidPing"><soap:fault name="InvalidPing" use="literal"/></fault>
+                </operation>
+            </binding>
+
+            <service name="PingService">
+                <port name="PingPort" binding="tns:PingBinding">
+                    <soap:address location="http://example.com/ping"/>
+                </port>
+            </service>
+        </definitions>"#,
+        "http://example.com/"
+    );
+}
+
+use generated::{services, types};
+
+fn ping(fault: &str) -> services::pingError {
+    let (url, _) = mock_server(&format!(
+        "<Fault><faultcode>soap:Client</faultcode><faultstring>Rejected</faultstring>{}</Fault>",
+        fault
+    ));
+
+    services::PingService::PingPort::with_location(url)
+        .ping(types::Ping {
+            value: "hello".to_owned(),
+        })
+        .unwrap_err()
+}
+
+#[test]
+fn declared_faults_are_returned_as_their_message() {
+    match ping("<detail><InvalidPing><reason>too short</reason></InvalidPing></detail>") {
+        services::pingError::InvalidPing(message) => assert_eq!(message.fault.reason, "too short"),
+        other => panic!("expected the declared fault, got {:?}", other),
+    }
+}
+
+#[test]
+fn other_faults_are_kept_as_soap_errors() {
+    match ping("<detail><Unexpected/></detail>") {
+        services::pingError::Fault(SoapError::Fault(fault)) => assert_eq!(fault.fault_string, "Rejected"),
+        other => panic!("expected a generic fault, got {:?}", other),
+    }
+}
@@ -0,0 +1,176 @@
+mod common;
+
+use common::{from_str, mock_server, to_string};
+use suds_util::error::SoapError;
+
+#[allow(dead_code, non_snake_case)]
+mod generated {
+    suds_macro::suds_str!(
+        r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/"
+                        xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+                        xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+                        xmlns:tns="http://example.com/inline"
+                        targetNamespace="http://example.com/inline">
+            <types>
+                <xsd:schema targetNamespace="http://example.com/inline">
+                    <xsd:element name="Ping">
+                        <xsd:complexType>
+                            <xsd:sequence>
+                                <xsd:element name="value" type="xsd:string"/>
+                            </xsd:sequence>
+                        </xsd:complexType>
+                    </xsd:element>
+
+                    <xsd:element name="PingResponse">
+                        <xsd:complexType>
+                            <xsd:sequence>
+                                <xsd:element name="result" type="xsd:string"/>
+                            </xsd:sequence>
+                        </xsd:complexType>
+                    </xsd:element>
+                </xsd:schema>
+            </types>
+
+            <message name="PingRequest">
+                <part name="parameters" element="tns:Ping"/>
+            </message>
+
+            <message name="PingResponse">
+                <part name="parameters" element="tns:PingResponse"/>
+            </message>
+
+            <portType name="PingPortType">
+                <operation name="ping">
+                    <input message="tns:PingRequest"/>
+                    <output message="tns:PingResponse"/>
+                </operation>
+            </portType>
+
+            <binding name="PingBinding" type="tns:PingPortType">
+                <soap:binding style="document" transport="http://schemas.xmlsoap.org/soap/http"/>
+                <operation name="ping">
+                    <soap:operation soapAction="http://example.com/inline/Ping" style="document"/>
+                    <input><soap:body use="literal"/></input>
+                    <output><soap:body use="literal"/></output>
+                </operation>
+            </binding>
+
+            <service name="PingService">
+                <port name="PingPort" binding="tns:PingBinding">
+                    <soap:address location="http://example.com/ping"/>
+                </port>
+            </service>
+        </definitions>"#,
+        "http://example.com/"
+    );
+}
+
+use generated::{messages, services, types};
+
+#[test]
+fn one_operation_service() {
+    let _: fn(&services::PingService::PingPort, messages::PingRequest) -> Result<messages::PingResponse, SoapError> =
+        services::PingService::PingPort::ping;
+
+    let request = messages::PingRequest {
+        parameters: types::Ping {
+            value: "hello".to_owned(),
+        },
+    };
+
+    assert_eq!(from_str::<messages::PingRequest>(&to_string(&request)), request);
+}
+
+struct FakePing;
+
+impl services::PingPortType for FakePing {
+    fn ping(&self, input: messages::PingRequest) -> Result<messages::PingResponse, SoapError> {
+        Ok(messages::PingResponse {
+            parameters: types::PingResponse {
+                result: input.parameters.value,
+            },
+        })
+    }
+}
+
+fn ping(client: &impl services::PingPortType, value: &str) -> String {
+    let request = messages::PingRequest {
+        parameters: types::Ping {
+            value: value.to_owned(),
+        },
+    };
+
+    client.ping(request).unwrap().parameters.result
+}
+
+#[test]
+fn ports_implement_their_port_type() {
+    fn implements<T: services::PingPortType>() {}
+    implements::<services::PingService::PingPort>();
+
+    assert_eq!(ping(&FakePing, "hello"), "hello");
+}
+
+#[test]
+fn messages_can_be_built() {
+    let built = messages::PingRequest::builder()
+        .parameters(types::Ping {
+            value: "hello".to_owned(),
+        })
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        built,
+        messages::PingRequest {
+            parameters: types::Ping {
+                value: "hello".to_owned()
+            }
+        }
+    );
+    assert!(messages::PingRequest::builder().build().is_err());
+}
+
+#[cfg(feature = "tls-client-auth")]
+#[test]
+fn ports_accept_an_identity() {
+    let identity = suds_util::soap::Identity::from_pkcs8_pem(
+        include_bytes!("../../util/tests/client.crt"),
+        include_bytes!("../../util/tests/client.key"),
+    )
+    .unwrap();
+
+    assert!(services::PingService::PingPort::with_identity(identity).is_ok());
+}
+
+#[test]
+fn single_part_messages_convert_from_their_part() {
+    let _: fn(&services::PingService::PingPort, types::Ping) -> Result<messages::PingResponse, SoapError> =
+        services::PingService::PingPort::ping;
+
+    let ping = types::Ping {
+        value: "hello".to_owned(),
+    };
+
+    assert_eq!(
+        messages::PingRequest::from(ping.clone()),
+        messages::PingRequest { parameters: ping }
+    );
+}
+
+#[test]
+fn ports_can_be_pointed_at_another_location() {
+    let (url, requests) = mock_server("<PingResponse><result>pong</result></PingResponse>");
+    let port = services::PingService::PingPort::with_location(url);
+
+    let response = port
+        .ping(types::Ping {
+            value: "hello".to_owned(),
+        })
+        .unwrap();
+
+    assert_eq!(response.parameters.result, "pong");
+
+    let sent = suds_util::soap::Envelope::<messages::PingRequest>::from_response(requests.recv().unwrap().as_bytes());
+    assert_eq!(sent.unwrap().into_body().parameters.value, "hello");
+}
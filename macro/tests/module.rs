@@ -0,0 +1,16 @@
+mod common;
+
+use common::{from_str, to_string};
+
+// Relative paths resolve from the workspace root, where cargo runs the compiler
+#[suds_macro::suds_module("macro/tests/types.wsdl")]
+#[allow(dead_code)]
+mod generated {}
+
+use generated::types;
+
+#[test]
+fn attribute_fills_the_module() {
+    let code = types::Code("KGX".to_owned());
+    assert_eq!(from_str::<types::Code>(&to_string(&code)), code);
+}
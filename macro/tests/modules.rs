@@ -0,0 +1,15 @@
+#![allow(dead_code)]
+
+mod bare {
+    suds_macro::suds_file!("tests/types.wsdl");
+}
+
+suds_macro::suds_file!(mod named = "tests/types.wsdl");
+
+#[test]
+fn bare_and_named_forms_emit_the_same_modules() {
+    assert_eq!(
+        std::any::type_name::<bare::types::Contact>().replace("bare", "named"),
+        std::any::type_name::<named::types::Contact>()
+    );
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use common::mock_server;
+use suds_util::soap::{Client, Proxy};
+
+#[allow(dead_code, non_camel_case_types)]
+mod generated {
+    suds_macro::suds_str!(
+        r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/"
+                        xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+                        xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+                        xmlns:tns="http://example.com/registry"
+                        targetNamespace="http://example.com/registry">
+            <types>
+                <xsd:schema targetNamespace="http://example.com/registry">
+                    <xsd:element name="Ping">
+                        <xsd:complexType>
+                            <xsd:sequence>
+                                <xsd:element name="value" type="xsd:string"/>
+                            </xsd:sequence>
+                        </xsd:complexType>
+                    </xsd:element>
+
+                    <xsd:element name="PingResponse">
+                        <xsd:complexType>
+                            <xsd:sequence>
+                                <xsd:element name="result" type="xsd:string"/>
+                            </xsd:sequence>
+                        </xsd:complexType>
+                    </xsd:element>
+                </xsd:schema>
+            </types>
+
+            <message name="PingRequest">
+                <part name="parameters" element="tns:Ping"/>
+            </message>
+
+            <message name="PingResponse">
+                <part name="parameters" element="tns:PingResponse"/>
+            </message>
+
+            <portType name="PingPortType">
+                <operation name="ping">
+                    <input message="tns:PingRequest"/>
+                    <output message="tns:PingResponse"/>
+                </operation>
+            </portType>
+
+            <binding name="PingBinding" type="tns:PingPortType">
+                <soap:binding style="document" transport="http://schemas.xmlsoap.org/soap/http"/>
+                <operation name="ping">
+                    <soap:operation soapAction="http://example.com/registry/Ping" style="document"/>
+                    <input><soap:body use="literal"/></input>
+                    <output><soap:body use="literal"/></output>
+                </operation>
+            </binding>
+
+            <service name="WeatherService">
+                <port name="weather" binding="tns:PingBinding">
+                    <soap:address location="http://weather.invalid/ping"/>
+                </port>
+            </service>
+
+            <service name="StockService">
+                <port name="stock" binding="tns:PingBinding">
+                    <soap:address location="http://stock.invalid/ping"/>
+                </port>
+            </service>
+        </definitions>"#,
+        "http://example.com/"
+    );
+}
+
+use generated::{
+    services::{PingPortType, ServiceRegistry, StockService, WeatherService},
+    types,
+};
+
+fn proxied_registry(result: &str) -> ServiceRegistry {
+    let (url, _) = mock_server(&format!("<PingResponse><result>{}</result></PingResponse>", result));
+    let client = Client::builder("http://unused").proxy(Proxy::http(url).unwrap()).build().unwrap();
+
+    ServiceRegistry::with_client(client)
+}
+
+fn ping(port: &impl PingPortType) -> String {
+    let request = types::Ping {
+        value: "hello".to_owned(),
+    };
+
+    port.ping(request.into()).unwrap().parameters.result
+}
+
+#[test]
+fn registry_holds_every_port() {
+    let registry = ServiceRegistry::default();
+
+    let _: &WeatherService::weather = &registry.weather_service_weather;
+    let _: &StockService::stock = &registry.stock_service_stock;
+}
+
+#[test]
+fn registry_ports_share_the_given_client() {
+    assert_eq!(ping(&proxied_registry("sunny").weather_service_weather), "sunny");
+    assert_eq!(ping(&proxied_registry("rising").stock_service_stock), "rising");
+}
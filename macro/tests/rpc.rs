@@ -0,0 +1,183 @@
+mod common;
+
+use common::mock_server;
+use suds_util::soap::{Envelope, Proxy, RpcBody};
+
+#[allow(dead_code, non_snake_case)]
+mod generated {
+    suds_macro::suds_str!(
+        r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/"
+                        xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+                        xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+                        xmlns:tns="http://example.com/rpc"
+                        targetNamespace="http://example.com/rpc">
+            <types>
+                <xsd:schema targetNamespace="http://example.com/rpc">
+                    <xsd:element name="Ping">
+                        <xsd:complexType>
+                            <xsd:sequence>
+                                <xsd:element name="value" type="xsd:string"/>
+                            </xsd:sequence>
+                        </xsd:complexType>
+                    </xsd:element>
+
+                    <xsd:element name="PingResponse">
+                        <xsd:complexType>
+                            <xsd:sequence>
+                                <xsd:element name="result" type="xsd:string"/>
+                            </xsd:sequence>
+                        </xsd:complexType>
+                    </xsd:element>
+                </xsd:schema>
+            </types>
+
+            <message name="PingRequest">
+                <part name="parameters" element="tns:Ping"/>
+            </message>
+
+            <message name="PingResponse">
+                <part name="parameters" element="tns:PingResponse"/>
+            </message>
+
+            <message name="NotifyRequest">
+                <part name="parameters" element="tns:Ping"/>
+            </message>
+
+            <portType name="PingPortType">
+                <operation name="ping">
+                    <input message="tns:PingRequest"/>
+                    <output message="tns:PingResponse"/>
+                </operation>
+
+                <operation name="notify">
+                    <input message="tns:NotifyRequest"/>
+                </operation>
+            </portType>
+
+            <binding name="DocumentBinding" type="tns:PingPortType">
+                <soap:binding style="document" transport="http://schemas.xmlsoap.org/soap/http"/>
+                <operation name="ping">
+                    <soap:operation soapAction="http://example.com/rpc/Ping" style="document"/>
+                    <input><soap:body use="literal"/></input>
+                    <output><soap:body use="literal"/></output>
+                </operation>
+                <operation name="notify">
+                    <soap:operation soapAction="http://example.com/rpc/Notify" style="document"/>
+                    <input><soap:body use="literal"/></input>
+                </operation>
+            </binding>
+
+            <binding name="RpcBinding" type="tns:PingPortType">
+                <soap:binding style="rpc" transport="http://schemas.xmlsoap.org/soap/http"/>
+                <operation name="ping">
+                    <soap:operation soapAction="http://example.com/rpc/Ping" style="rpc"/>
+                    <input><soap:body use="literal" namespace="http://example.com/rpc/operations"/></input>
+                    <output><soap:body use="literal"/></output>
+                </operation>
+                <operation name="notify">
+                    <soap:operation soapAction="http://example.com/rpc/Notify" style="rpc"/>
+                    <input><soap:body use="literal" namespace="http://example.com/rpc/operations"/></input>
+                </operation>
+            </binding>
+
+            <service name="PingService">
+                <port name="DocumentPort" binding="tns:DocumentBinding">
+                    <soap:address location="http://service.invalid/document"/>
+                </port>
+
+                <port name="RpcPort" binding="tns:RpcBinding">
+                    <soap:address location="http://service.invalid/rpc"/>
+                </port>
+            </service>
+        </definitions>"#,
+        "http://example.com/"
+    );
+}
+
+use generated::{messages, services::PingService, types};
+
+fn request() -> messages::PingRequest {
+    messages::PingRequest {
+        parameters: types::Ping {
+            value: "hello".to_owned(),
+        },
+    }
+}
+
+fn response() -> messages::PingResponse {
+    messages::PingResponse {
+        parameters: types::PingResponse {
+            result: "pong".to_owned(),
+        },
+    }
+}
+
+#[test]
+fn document_bodies_are_sent_as_is() {
+    let (url, requests) = mock_server("<PingResponse><result>pong</result></PingResponse>");
+    let port = PingService::DocumentPort::with_proxy(Proxy::http(url).unwrap()).unwrap();
+
+    assert_eq!(port.ping(request()).unwrap(), response());
+
+    let sent = Envelope::<messages::PingRequest>::from_response(requests.recv().unwrap().as_bytes()).unwrap();
+    assert_eq!(sent.into_body(), request());
+}
+
+#[test]
+fn rpc_bodies_are_wrapped_in_the_operation() {
+    let (url, requests) = mock_server("<pingResponse><PingResponse><result>pong</result></PingResponse></pingResponse>");
+    let port = PingService::RpcPort::with_proxy(Proxy::http(url).unwrap()).unwrap();
+
+    assert_eq!(port.ping(request()).unwrap(), response());
+
+    let sent = Envelope::<RpcBody<messages::PingRequest>>::from_response(requests.recv().unwrap().as_bytes())
+        .unwrap()
+        .into_body();
+    assert_eq!(sent.name(), "ping");
+    assert_eq!(sent.namespace(), "http://example.com/rpc/operations");
+    assert_eq!(sent.into_inner(), request());
+}
+
+#[test]
+fn one_way_operations_only_send() {
+    let (url, requests) = mock_server("");
+    let port = PingService::DocumentPort::with_proxy(Proxy::http(url).unwrap()).unwrap();
+
+    port.notify(request().parameters).unwrap();
+
+    let sent = Envelope::<messages::NotifyRequest>::from_response(requests.recv().unwrap().as_bytes()).unwrap();
+    assert_eq!(sent.into_body().parameters, request().parameters);
+
+    let (url, requests) = mock_server("");
+    let port = PingService::RpcPort::with_proxy(Proxy::http(url).unwrap()).unwrap();
+
+    port.notify(request().parameters).unwrap();
+
+    let sent = Envelope::<RpcBody<messages::NotifyRequest>>::from_response(requests.recv().unwrap().as_bytes())
+        .unwrap()
+        .into_body();
+    assert_eq!(sent.name(), "notify");
+    assert_eq!(sent.into_inner().parameters, request().parameters);
+}
+
+#[test]
+fn ports_expose_their_endpoint_and_actions() {
+    assert_eq!(PingService::DocumentPort::ENDPOINT, "http://service.invalid/document");
+    assert_eq!(PingService::RpcPort::ENDPOINT, "http://service.invalid/rpc");
+    assert_eq!(PingService::RpcPort::PING_SOAP_ACTION, "http://example.com/rpc/Ping");
+}
+
+#[cfg(feature = "raw-operations")]
+#[test]
+fn raw_operations_return_the_response_verbatim() {
+    let (url, requests) = mock_server("<PingResponse><result>pong</result></PingResponse>");
+    let port = PingService::DocumentPort::with_proxy(Proxy::http(url).unwrap()).unwrap();
+
+    assert_eq!(
+        port.ping_raw(request()).unwrap(),
+        "<Envelope><Body><PingResponse><result>pong</result></PingResponse></Body></Envelope>"
+    );
+
+    let sent = Envelope::<messages::PingRequest>::from_response(requests.recv().unwrap().as_bytes()).unwrap();
+    assert_eq!(sent.into_body(), request());
+}
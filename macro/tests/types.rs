@@ -0,0 +1,433 @@
+mod common;
+
+use common::{from_str, to_string, try_from_str};
+use suds_util::{
+    error::XmlError,
+    xml::{FromXml, Reader},
+};
+
+#[allow(dead_code)]
+mod generated {
+    suds_macro::suds_file!("tests/types.wsdl");
+}
+
+use generated::{messages, types};
+
+#[test]
+fn integer_types_keep_their_range() {
+    let integers = types::Integers {
+        byte: i8::MIN,
+        short: i16::MIN,
+        int: i32::MIN,
+        long: i64::MIN,
+        unsigned_byte: u8::MAX,
+        unsigned_int: u32::MAX,
+        unsigned_long: u64::MAX,
+    };
+
+    let xml = to_string(&integers);
+    assert!(xml.contains(">-9223372036854775808<"), "{}", xml);
+
+    let read: types::Integers = from_str(&xml);
+    assert_eq!(read.byte, i8::MIN);
+    assert_eq!(read.short, i16::MIN);
+    assert_eq!(read.int, i32::MIN);
+    assert_eq!(read.long, i64::MIN);
+    assert_eq!(read.unsigned_byte, u8::MAX);
+    assert_eq!(read.unsigned_int, u32::MAX);
+    assert_eq!(read.unsigned_long, u64::MAX);
+
+    let overflow = xml.replace(">4294967295<", ">4294967296<");
+    assert!(try_from_str::<types::Integers>(&overflow).is_err());
+}
+
+#[test]
+fn decimal_round_trips() {
+    let measurement = types::Measurement {
+        amount: 12.5,
+        ratio: 0.25,
+        distance: -1234.0625,
+    };
+
+    let xml = to_string(&measurement);
+    assert!(xml.contains(">12.5<"), "{}", xml);
+
+    let read: types::Measurement = from_str(&xml);
+    assert_eq!(read.amount, 12.5);
+    assert_eq!(read.ratio, 0.25);
+    assert_eq!(read.distance, -1234.0625);
+}
+
+#[test]
+fn simple_type_writes_its_value() {
+    let xml = to_string(&types::Code("KGX".to_owned()));
+    assert!(xml.contains(">KGX<"), "{}", xml);
+
+    let read: types::Code = from_str(&xml);
+    assert_eq!(read.0, "KGX");
+}
+
+#[test]
+fn optional_elements() {
+    let contact = types::Contact {
+        name: "Ada".to_owned(),
+        phone: None,
+    };
+
+    let xml = to_string(&contact);
+    assert!(!xml.contains("phone"), "{}", xml);
+
+    let read: types::Contact = from_str(&xml);
+    assert_eq!(read.name, "Ada");
+    assert_eq!(read.phone, None);
+
+    let read: types::Contact = from_str(
+        r#"<tns:Contact xmlns:tns="http://example.com/types"><tns:name>Ada</tns:name><tns:phone>555</tns:phone></tns:Contact>"#,
+    );
+    assert_eq!(read.phone.as_deref(), Some("555"));
+}
+
+#[test]
+fn repeated_elements() {
+    let read: types::Departures = from_str(
+        r#"<tns:Departures xmlns:tns="http://example.com/types">
+            <tns:service>first</tns:service>
+            <tns:service>second</tns:service>
+            <tns:service>third</tns:service>
+        </tns:Departures>"#,
+    );
+    assert_eq!(read.service, ["first", "second", "third"]);
+
+    let read: types::Departures = from_str(&to_string(&read));
+    assert_eq!(read.service, ["first", "second", "third"]);
+}
+
+#[test]
+fn enumerations_keep_their_values() {
+    assert_eq!(types::Progress::In_progress.as_str(), "in-progress");
+
+    let xml = to_string(&types::Progress::Value2nd);
+    assert!(xml.contains(">2nd<"), "{}", xml);
+
+    let read: types::Progress = from_str(&xml);
+    assert!(matches!(read, types::Progress::Value2nd));
+
+    let read = try_from_str::<types::Progress>(&xml.replace(">2nd<", ">third<"));
+    assert!(matches!(read, Err(XmlError::InvalidValue { value, .. }) if value == "third"));
+}
+
+#[test]
+fn attributes_round_trip() {
+    let price = types::Price {
+        amount: 9.99,
+        currency: "GBP".to_owned(),
+    };
+
+    let xml = to_string(&price);
+    assert!(xml.contains(r#"currency="GBP""#), "{}", xml);
+
+    let read: types::Price = from_str(&xml);
+    assert_eq!(read.amount, 9.99);
+    assert_eq!(read.currency, "GBP");
+}
+
+#[test]
+fn choice_branches() {
+    let read: types::Payment = from_str(
+        r#"<tns:Payment xmlns:tns="http://example.com/types"><tns:card>1234</tns:card></tns:Payment>"#,
+    );
+    assert!(matches!(read, types::Payment::Card(ref card) if card == "1234"));
+
+    let read: types::Payment = from_str(
+        r#"<tns:Payment xmlns:tns="http://example.com/types"><tns:cash>5.5</tns:cash></tns:Payment>"#,
+    );
+    assert!(matches!(read, types::Payment::Cash(cash) if cash == 5.5));
+
+    let read: types::Payment = from_str(&to_string(&read));
+    assert!(matches!(read, types::Payment::Cash(cash) if cash == 5.5));
+}
+
+#[test]
+fn identical_messages_are_equal() {
+    let quote = || messages::Quote {
+        price: types::Price {
+            amount: 1.5,
+            currency: "GBP".to_owned(),
+        },
+    };
+
+    assert_eq!(quote(), quote());
+    assert_ne!(quote(), messages::Quote::default());
+}
+
+#[test]
+fn nillable_elements() {
+    let read: types::Reading = from_str(
+        r#"<tns:Reading xmlns:tns="http://example.com/types" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><tns:value xsi:nil="true"/></tns:Reading>"#,
+    );
+    assert_eq!(read.value, None);
+
+    let xml = to_string(&read);
+    assert!(xml.contains(r#"xsi:nil="true""#), "{}", xml);
+    assert_eq!(from_str::<types::Reading>(&xml), read);
+
+    let read: types::Reading = from_str(
+        r#"<tns:Reading xmlns:tns="http://example.com/types"><tns:value>42</tns:value></tns:Reading>"#,
+    );
+    assert_eq!(read.value, Some(42));
+}
+
+#[test]
+fn binary_round_trips() {
+    let xml = to_string(&types::Attachment {
+        data: vec![0, 159, 146, 150, 255, b'a'],
+    });
+    assert!(xml.contains(">AJ+Slv9h<"), "{}", xml);
+
+    for data in [vec![0, 159, 146, 150, 255, b'a'], Vec::new()] {
+        let attachment = types::Attachment { data };
+        assert_eq!(from_str::<types::Attachment>(&to_string(&attachment)), attachment);
+    }
+}
+
+#[test]
+fn unknown_elements_are_skipped() {
+    let xml = r#"<tns:Contact xmlns:tns="http://example.com/types">
+        <tns:name>Ada</tns:name>
+        <tns:version><major>2</major></tns:version>
+    </tns:Contact>"#;
+
+    assert!(try_from_str::<types::Contact>(xml).is_err());
+
+    let mut reader = Reader::from_reader(xml.as_bytes());
+    reader.trim_text(true);
+    reader.expand_empty_elements(true);
+    reader.skip_unknown(true);
+
+    let read = types::Contact::from_xml(&mut reader, &mut Vec::new()).unwrap();
+    assert_eq!(read.name, "Ada");
+    assert_eq!(read.phone, None);
+}
+
+#[test]
+fn element_references() {
+    let person: types::Person = from_str(
+        r#"<tns:Person xmlns:tns="http://example.com/types"><tns:Address><tns:street>Main Street</tns:street></tns:Address></tns:Person>"#,
+    );
+    assert_ne!(person, types::Person::default());
+
+    let xml = to_string(&person);
+    assert!(xml.contains(">Main Street<"), "{}", xml);
+    assert_eq!(from_str::<types::Person>(&xml), person);
+}
+
+#[test]
+fn inline_simple_types() {
+    let order: types::Order = from_str(
+        r#"<tns:Order xmlns:tns="http://example.com/types"><tns:status>closed</tns:status><tns:note>Delivered</tns:note></tns:Order>"#,
+    );
+    assert_eq!(order.status, types::status::Closed);
+    assert_eq!(order.note, "Delivered");
+
+    assert_eq!(from_str::<types::Order>(&to_string(&order)), order);
+}
+
+#[test]
+fn multi_field_inline_types_are_flattened() {
+    let parcel: types::Parcel = from_str(
+        r#"<tns:Parcel xmlns:tns="http://example.com/types"><tns:dimensions><tns:width>3</tns:width><tns:height>4</tns:height></tns:dimensions></tns:Parcel>"#,
+    );
+    assert_eq!((parcel.width, parcel.height), (3, 4));
+
+    let xml = to_string(&parcel);
+    assert!(xml.contains("dimensions>"), "{}", xml);
+    assert_eq!(from_str::<types::Parcel>(&xml), parcel);
+}
+
+#[test]
+fn aliases() {
+    let total: types::Total = 2.5;
+    let quote: types::Quote = types::Price {
+        amount: total,
+        currency: "GBP".to_owned(),
+    };
+
+    assert_eq!(from_str::<types::Price>(&to_string(&quote)), quote);
+
+    // An element named after its own type is the type itself
+    let reading: types::Reading = from_str(
+        r#"<tns:Reading xmlns:tns="http://example.com/types"><tns:value>1</tns:value></tns:Reading>"#,
+    );
+    assert_eq!(reading.value, Some(1));
+}
+
+#[test]
+fn malformed_documents_are_errors() {
+    assert!(try_from_str::<types::Contact>(r#"<tns:Contact xmlns:tns="http://example.com/types"><tns:name>Ada"#).is_err());
+    assert!(try_from_str::<types::Measurement>(
+        r#"<tns:Measurement xmlns:tns="http://example.com/types"><tns:amount>lots</tns:amount></tns:Measurement>"#
+    )
+    .is_err());
+}
+
+#[test]
+fn empty_elements() {
+    let contact: types::Contact =
+        from_str(r#"<tns:Contact xmlns:tns="http://example.com/types"><tns:name/></tns:Contact>"#);
+    assert_eq!(contact.name, "");
+
+    let survey: types::Survey = from_str("<Survey><score/><comment/></Survey>");
+    assert_eq!(survey.score, None);
+    assert_eq!(survey.comment.as_deref(), Some(""));
+
+    let survey: types::Survey = from_str("<Survey><score>4</score></Survey>");
+    assert_eq!(survey.score, Some(4));
+    assert_eq!(survey.comment, None);
+}
+
+#[test]
+fn any_content_is_kept_as_raw_xml() {
+    let extensible: types::Extensible = from_str(
+        r#"<tns:Extensible xmlns:tns="http://example.com/types"><tns:payload><item id="1"><name>first</name></item></tns:payload><extra>text</extra></tns:Extensible>"#,
+    );
+
+    let xml = to_string(&extensible);
+    assert!(xml.contains(r#"<item id="1"><name>first</name></item>"#), "{}", xml);
+    assert!(xml.contains("<extra>text</extra>"), "{}", xml);
+    assert_eq!(from_str::<types::Extensible>(&xml), extensible);
+}
+
+#[test]
+fn responses_match_whatever_the_prefix() {
+    let contact = types::Contact {
+        name: "Ada".to_owned(),
+        phone: Some("555".to_owned()),
+    };
+
+    for xml in [
+        r#"<tns:Contact xmlns:tns="http://example.com/types"><tns:name>Ada</tns:name><tns:phone>555</tns:phone></tns:Contact>"#,
+        r#"<Contact xmlns="http://example.com/types"><name>Ada</name><phone>555</phone></Contact>"#,
+        r#"<Contact><name>Ada</name><phone>555</phone></Contact>"#,
+    ] {
+        assert_eq!(from_str::<types::Contact>(xml), contact, "{}", xml);
+    }
+
+    assert!(try_from_str::<types::Contact>(
+        r#"<Contact xmlns="http://example.com/other"><name>Ada</name><phone>555</phone></Contact>"#
+    )
+    .is_err());
+}
+
+#[test]
+fn list_types_are_space_separated() {
+    let ids: types::Ids = from_str("<Ids>1 2  3</Ids>");
+    assert_eq!(ids, types::Ids(vec![1, 2, 3]));
+
+    let xml = to_string(&ids);
+    assert!(xml.contains(">1 2 3<"), "{}", xml);
+    assert_eq!(from_str::<types::Ids>(&xml), ids);
+
+    assert_eq!(from_str::<types::Ids>("<Ids/>"), types::Ids(Vec::new()));
+    assert!(try_from_str::<types::Ids>("<Ids>1 two</Ids>").is_err());
+}
+
+#[test]
+fn substitution_group_members() {
+    let car: types::VehicleGroup = from_str("<Car><seats>4</seats></Car>");
+    assert_eq!(car, types::VehicleGroup::Car(types::Car { seats: 4 }));
+
+    let bike: types::VehicleGroup = from_str("<Bike><gears>21</gears></Bike>");
+    assert_eq!(bike, types::VehicleGroup::Bike(types::Bike { gears: 21 }));
+
+    let garage: types::Garage = from_str(
+        "<Garage><Vehicle><wheels>3</wheels></Vehicle><Bike><gears>21</gears></Bike><Car><seats>4</seats></Car></Garage>",
+    );
+    assert_eq!(from_str::<types::Garage>(&to_string(&garage)), garage);
+    assert_ne!(garage, from_str("<Garage><Vehicle><wheels>3</wheels></Vehicle></Garage>"));
+
+    assert!(try_from_str::<types::VehicleGroup>("<Boat><sails>2</sails></Boat>").is_err());
+    assert!(try_from_str::<types::Garage>("<Garage><Boat><sails>2</sails></Boat></Garage>").is_err());
+}
+
+#[test]
+fn simple_content_with_attributes() {
+    let amount: types::Amount = from_str(r#"<Amount currency="USD">10.5</Amount>"#);
+    assert_eq!(
+        amount,
+        types::Amount {
+            value: 10.5,
+            currency: Some("USD".to_owned())
+        }
+    );
+
+    assert_eq!(from_str::<types::Amount>(&to_string(&amount)), amount);
+}
+
+#[test]
+fn simple_content_read_after_attributes() {
+    let amount: types::Amount = from_str(r#"<Amount currency="USD"><![CDATA[10.00]]></Amount>"#);
+    assert_eq!(
+        amount,
+        types::Amount {
+            value: 10.0,
+            currency: Some("USD".to_owned())
+        }
+    );
+}
+
+#[test]
+fn members_keep_their_xml_names() {
+    let gauge: types::Gauge =
+        from_str(r#"<Gauge unitName="celsius"><sensorId>north</sensorId><maxValue>21</maxValue></Gauge>"#);
+
+    assert_ne!(gauge, types::Gauge::default());
+    assert_eq!(from_str::<types::Gauge>(&to_string(&gauge)), gauge);
+}
+
+#[test]
+fn simple_types_display_their_xml_value() {
+    assert_eq!(types::Code("BSK".to_owned()).to_string(), "BSK");
+    assert_eq!(types::Progress::In_progress.to_string(), "in-progress");
+}
+
+#[test]
+fn string_derived_types() {
+    let identifiers = types::Identifiers {
+        uri: "http://example.com/a?b=c".to_owned(),
+        qname: "tns:Ping".to_owned(),
+        token: "one two".to_owned(),
+        normalized: "a b".to_owned(),
+        language: "en-GB".to_owned(),
+        name: "_name".to_owned(),
+        ncname: "local".to_owned(),
+        nmtoken: "2nd".to_owned(),
+    };
+
+    assert_eq!(from_str::<types::Identifiers>(&to_string(&identifiers)), identifiers);
+
+    let empty: types::Identifiers = from_str(
+        "<Identifiers><uri/><qname/><token/><normalized/><language/><name/><ncname/><nmtoken/></Identifiers>",
+    );
+    assert_eq!(empty, types::Identifiers::default());
+}
+
+#[test]
+fn type_parts_are_unqualified_elements() {
+    let lookup = messages::Lookup {
+        code: types::Code("KGX".to_owned()),
+        limit: 3,
+    };
+
+    assert_eq!(from_str::<messages::Lookup>("<code>KGX</code><limit>3</limit>"), lookup);
+    assert_eq!(from_str::<messages::Lookup>(&to_string(&lookup)), lookup);
+}
+
+#[test]
+fn enumerations_parse_from_strings() {
+    assert_eq!("in-progress".parse::<types::Progress>().unwrap(), types::Progress::In_progress);
+    assert_eq!(types::Progress::try_from("2nd").unwrap(), types::Progress::Value2nd);
+    assert!(matches!(
+        "Nope".parse::<types::Progress>(),
+        Err(suds_util::error::ValidationError::Enumeration(value)) if value == "Nope"
+    ));
+}
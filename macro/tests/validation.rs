@@ -0,0 +1,137 @@
+mod common;
+
+use common::{from_str, try_from_str};
+use suds_util::{
+    error::{ValidationError, XmlError},
+    xml::{FromXml, Reader, Validate},
+};
+
+#[allow(dead_code)]
+mod generated {
+    suds_macro::suds_str!(
+        r#"<?xml version="1.0"?>
+<definitions xmlns="http://schemas.xmlsoap.org/wsdl/"
+             xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+             targetNamespace="http://example.com/validation">
+    <types>
+        <xsd:schema targetNamespace="http://example.com/validation">
+            <xsd:simpleType name="Status">
+                <xsd:restriction base="xsd:string">
+                    <xsd:enumeration value="Active"/>
+                    <xsd:enumeration value="Suspended"/>
+                </xsd:restriction>
+            </xsd:simpleType>
+
+            <xsd:simpleType name="Percentage">
+                <xsd:restriction base="xsd:int">
+                    <xsd:minInclusive value="0"/>
+                    <xsd:maxInclusive value="100"/>
+                </xsd:restriction>
+            </xsd:simpleType>
+
+            <xsd:simpleType name="Temperature">
+                <xsd:restriction base="xsd:short">
+                    <xsd:minInclusive value="-40"/>
+                </xsd:restriction>
+            </xsd:simpleType>
+
+            <xsd:simpleType name="Ratio">
+                <xsd:restriction base="xsd:double">
+                    <xsd:maxInclusive value="1.5"/>
+                </xsd:restriction>
+            </xsd:simpleType>
+
+            <xsd:simpleType name="Code">
+                <xsd:restriction base="xsd:string">
+                    <xsd:maxLength value="3"/>
+                    <xsd:pattern value="[A-Z]+"/>
+                    <xsd:pattern value="[0-9]+"/>
+                </xsd:restriction>
+            </xsd:simpleType>
+        </xsd:schema>
+    </types>
+</definitions>"#,
+        "http://example.com/"
+    );
+}
+
+use generated::types::{Code, Percentage, Ratio, Status, Temperature};
+
+fn validated<T: FromXml>(xml: &str) -> Result<T, XmlError> {
+    let mut reader = Reader::from_reader(xml.as_bytes());
+    reader.trim_text(true);
+    reader.expand_empty_elements(true);
+    reader.validate(true);
+
+    T::from_xml(&mut reader, &mut Vec::new())
+}
+
+#[test]
+fn unknown_enumeration_value_is_an_error() {
+    assert_eq!(from_str::<Status>("<Status>Suspended</Status>"), Status::Suspended);
+
+    assert!(matches!(
+        try_from_str::<Status>("<Status>Closed</Status>"),
+        Err(XmlError::InvalidValue { value, .. }) if value == "Closed"
+    ));
+}
+
+#[test]
+fn facets_are_only_checked_when_asked() {
+    let percentage = from_str::<Percentage>("<Percentage>101</Percentage>");
+
+    assert_eq!(percentage, Percentage(101));
+    assert!(matches!(percentage.validate(), Err(ValidationError::Range(value)) if value == "101"));
+}
+
+#[test]
+fn inclusive_range() {
+    assert_eq!(validated::<Percentage>("<Percentage>0</Percentage>").unwrap(), Percentage(0));
+    assert_eq!(validated::<Percentage>("<Percentage>100</Percentage>").unwrap(), Percentage(100));
+
+    assert!(matches!(
+        validated::<Percentage>("<Percentage>101</Percentage>"),
+        Err(XmlError::Validation { source: ValidationError::Range(value), .. }) if value == "101"
+    ));
+
+    assert!(matches!(
+        validated::<Percentage>("<Percentage>-1</Percentage>"),
+        Err(XmlError::Validation { source: ValidationError::Range(_), .. })
+    ));
+}
+
+#[test]
+fn negative_bound() {
+    assert_eq!(validated::<Temperature>("<Temperature>-40</Temperature>").unwrap(), Temperature(-40));
+
+    assert!(matches!(
+        validated::<Temperature>("<Temperature>-41</Temperature>"),
+        Err(XmlError::Validation { source: ValidationError::Range(_), .. })
+    ));
+}
+
+#[test]
+fn float_bound() {
+    assert_eq!(validated::<Ratio>("<Ratio>1.5</Ratio>").unwrap(), Ratio(1.5));
+
+    assert!(matches!(
+        validated::<Ratio>("<Ratio>1.75</Ratio>"),
+        Err(XmlError::Validation { source: ValidationError::Range(_), .. })
+    ));
+}
+
+#[test]
+fn length_and_patterns() {
+    assert_eq!(validated::<Code>("<Code>ABC</Code>").unwrap(), Code("ABC".to_owned()));
+    assert_eq!(validated::<Code>("<Code>123</Code>").unwrap(), Code("123".to_owned()));
+
+    assert!(matches!(
+        validated::<Code>("<Code>A1</Code>"),
+        Err(XmlError::Validation { source: ValidationError::Pattern(value), .. }) if value == "A1"
+    ));
+
+    assert!(matches!(
+        validated::<Code>("<Code>ABCD</Code>"),
+        Err(XmlError::Validation { source: ValidationError::Length(4), .. })
+    ));
+}
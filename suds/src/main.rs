@@ -1,5 +1,6 @@
-use std::{fs::File, io::Write};
+use std::{collections::HashSet, fs::File, io::Write};
 
+use quote::quote;
 use structopt::StructOpt;
 use thiserror::Error;
 
@@ -16,6 +17,12 @@ enum Error {
 
     #[error("Error")]
     IoError(#[from] std::io::Error),
+
+    #[error("Module name {0} is used by more than one input, use --module-name to tell them apart")]
+    DuplicateModule(String),
+
+    #[error("Module name {0} is not a valid Rust identifier")]
+    InvalidModule(String),
 }
 
 #[derive(StructOpt)]
@@ -23,17 +30,60 @@ struct Args {
     #[structopt(short, long, default_value = "./output.rs")]
     output: String,
 
-    input: String,
+    #[structopt(long)]
+    module_name: Vec<String>,
+
+    #[structopt(required = true)]
+    inputs: Vec<String>,
+}
+
+fn module_name(definition: &wsdl::types::Definition, index: usize) -> String {
+    match definition.services.first() {
+        Some(service) => codegen::snake_case(&service.name.name),
+        None => format!("service{}", index),
+    }
 }
 
 #[paw::main]
 fn main(args: Args) -> Result<(), Error> {
     {
-        let tokens = codegen::from_url(args.input)?;
-        let ast: syn::File = syn::parse2(tokens)?;
+        let wrap = args.inputs.len() > 1 || !args.module_name.is_empty();
+        let mut tokens = quote! {};
+        let mut names = HashSet::new();
+
+        for (index, input) in args.inputs.iter().enumerate() {
+            let (definition, namespaces) = wsdl::parse(input)?;
+            let module = codegen::from_definition(&definition, &namespaces)?;
+
+            if wrap {
+                let name = match args.module_name.get(index) {
+                    Some(name) => name.clone(),
+                    None => module_name(&definition, index),
+                };
+
+                if !names.insert(name.clone()) {
+                    return Err(Error::DuplicateModule(name));
+                }
+
+                let name = syn::parse_str::<syn::Ident>(&name).map_err(|_| Error::InvalidModule(name))?;
+                tokens.extend(quote! {
+                    pub mod #name {
+                        #module
+                    }
+                });
+            } else {
+                tokens.extend(module);
+            }
+        }
+
+        let output = codegen::pretty(tokens)?;
 
-        let mut file = File::create(&args.output)?;
-        write!(&mut file, "{}", prettyplease::unparse(&ast))?;
+        if args.output == "-" {
+            write!(std::io::stdout().lock(), "{}", output)?;
+        } else {
+            let mut file = File::create(&args.output)?;
+            write!(&mut file, "{}", output)?;
+        }
     }
 
     Ok(())
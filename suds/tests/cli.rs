@@ -0,0 +1,130 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+fn wsdl(service: &str) -> String {
+    format!(
+        r#"<?xml version="1.0"?>
+<definitions xmlns="http://schemas.xmlsoap.org/wsdl/"
+             xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+             xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+             xmlns:tns="http://example.com/{service}"
+             targetNamespace="http://example.com/{service}">
+    <types>
+        <xsd:schema targetNamespace="http://example.com/{service}">
+            <xsd:element name="Ping" type="xsd:string"/>
+        </xsd:schema>
+    </types>
+    <message name="PingRequest">
+        <part name="value" element="tns:Ping"/>
+    </message>
+    <portType name="{service}PortType">
+        <operation name="Ping">
+            <input message="tns:PingRequest"/>
+        </operation>
+    </portType>
+    <binding name="{service}Binding" type="tns:{service}PortType">
+        <soap:binding transport="http://schemas.xmlsoap.org/soap/http"/>
+        <operation name="Ping">
+            <soap:operation soapAction="ping" style="document"/>
+            <input><soap:body use="literal"/></input>
+        </operation>
+    </binding>
+    <service name="{service}">
+        <port name="{service}Port" binding="tns:{service}Binding">
+            <soap:address location="http://example.com/{service}"/>
+        </port>
+    </service>
+</definitions>"#,
+        service = service
+    )
+}
+
+fn write_inputs(test: &str, services: &[&str]) -> (PathBuf, Vec<PathBuf>) {
+    let directory = env::temp_dir().join(format!("suds-cli-{}-{}", test, std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+
+    let inputs = services
+        .iter()
+        .enumerate()
+        .map(|(index, service)| {
+            let path = directory.join(format!("{}.wsdl", index));
+            fs::write(&path, wsdl(service)).unwrap();
+            path
+        })
+        .collect();
+
+    (directory, inputs)
+}
+
+fn modules(output: &Path) -> Vec<String> {
+    let file = syn::parse_file(&fs::read_to_string(output).unwrap()).unwrap();
+
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Mod(module) => Some(module.ident.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn generates_a_module_per_input() {
+    let (directory, inputs) = write_inputs("modules", &["WeatherService", "StockQuote"]);
+    let output = directory.join("output.rs");
+
+    let status = Command::new(env!("CARGO_BIN_EXE_suds"))
+        .arg("--output")
+        .arg(&output)
+        .args(&inputs)
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+    assert_eq!(modules(&output), ["weather_service", "stock_quote"]);
+
+    fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn rejects_duplicate_module_names() {
+    let (directory, inputs) = write_inputs("duplicates", &["WeatherService", "WeatherService"]);
+    let output = directory.join("output.rs");
+
+    let result = Command::new(env!("CARGO_BIN_EXE_suds"))
+        .arg("--output")
+        .arg(&output)
+        .args(&inputs)
+        .output()
+        .unwrap();
+
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("weather_service"));
+    assert!(!output.exists());
+
+    fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn rejects_module_names_that_are_not_identifiers() {
+    let (directory, inputs) = write_inputs("identifiers", &["WeatherService"]);
+    let output = directory.join("output.rs");
+
+    let result = Command::new(env!("CARGO_BIN_EXE_suds"))
+        .arg("--output")
+        .arg(&output)
+        .args(&inputs)
+        .arg("--module-name")
+        .arg("weather-service")
+        .output()
+        .unwrap();
+
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("InvalidModule(\"weather-service\")"));
+    assert!(!output.exists());
+
+    fs::remove_dir_all(directory).unwrap();
+}
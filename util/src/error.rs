@@ -0,0 +1,99 @@
+use thiserror::Error;
+
+use super::soap::SoapFault;
+
+#[derive(Debug, Error)]
+pub enum SoapError {
+    #[error("Unable to build HTTP client")]
+    BuildError(reqwest::Error),
+
+    #[error("Unable to compress request body")]
+    CompressionError(std::io::Error),
+
+    #[error("Unable to send request")]
+    RequestError(#[from] reqwest::Error),
+
+    #[error("Server responded with status {status}")]
+    StatusError { status: reqwest::StatusCode, body: String },
+
+    #[error("Unable to read response body")]
+    BodyError(reqwest::Error),
+
+    #[error("Server returned a fault: {}", .0.fault_string)]
+    Fault(SoapFault),
+
+    #[error("Unable to parse multipart response body")]
+    MultipartError,
+
+    #[error("Unable to deserialize response body")]
+    Deserialize { body: String, source: XmlError },
+}
+
+#[derive(Debug, Error)]
+pub enum XmlError {
+    #[error("Malformed XML at position {position}")]
+    Xml {
+        source: quick_xml::Error,
+        position: usize,
+    },
+
+    #[error("Unexpected end of document")]
+    UnexpectedEof,
+
+    #[error("Expected start of element {name} at position {position}")]
+    ExpectedStart { name: String, position: usize },
+
+    #[error("Expected end of element at position {position}")]
+    ExpectedEnd { position: usize },
+
+    #[error("Expected text at position {position}")]
+    ExpectedText { position: usize },
+
+    #[error("Expected a value but found an empty element at position {position}")]
+    EmptyValue { position: usize },
+
+    #[error("Invalid value {value:?} at position {position}")]
+    InvalidValue { value: String, position: usize },
+
+    #[error("Missing attachment {id} at position {position}")]
+    MissingAttachment { id: String, position: usize },
+
+    #[error("Missing attribute {name} at position {position}")]
+    MissingAttribute { name: String, position: usize },
+
+    #[error("Value failed validation at position {position}")]
+    Validation {
+        source: ValidationError,
+        position: usize,
+    },
+}
+
+impl From<XmlError> for SoapError {
+    fn from(source: XmlError) -> Self {
+        Self::Deserialize {
+            body: String::new(),
+            source,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ValidationError {
+    #[error("Value {0:?} is not one of the allowed values")]
+    Enumeration(String),
+
+    #[error("Length {0} is outside of the allowed range")]
+    Length(usize),
+
+    #[error("Value {0} is outside of the allowed range")]
+    Range(String),
+
+    #[error("Value {0:?} doesn't match the allowed patterns")]
+    Pattern(String),
+}
+
+#[derive(Debug, Error)]
+pub enum BuildError {
+    #[error("Missing required field {0}")]
+    MissingField(&'static str),
+}
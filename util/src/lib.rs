@@ -1,2 +1,10 @@
+#![deny(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)]
+
+pub mod error;
+pub mod mtom;
+pub mod security;
 pub mod soap;
 pub mod xml;
+
+#[cfg(feature = "chrono")]
+pub use chrono;
@@ -0,0 +1,155 @@
+use std::{cell::RefCell, collections::HashMap};
+
+#[derive(Debug, Clone)]
+pub struct Multipart {
+    pub root: Vec<u8>,
+    pub attachments: HashMap<String, Vec<u8>>,
+}
+
+pub fn is_multipart(content_type: &str) -> bool {
+    content_type
+        .trim_start()
+        .to_ascii_lowercase()
+        .starts_with("multipart/related")
+}
+
+fn parameter<'a>(content_type: &'a str, name: &str) -> Option<&'a str> {
+    content_type
+        .split(';')
+        .skip(1)
+        .filter_map(|parameter| parameter.split_once('='))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim().trim_matches('"'))
+}
+
+fn content_id(id: &str) -> &str {
+    id.trim().trim_start_matches('<').trim_end_matches('>')
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|index| index + from)
+}
+
+pub fn parse(content_type: &str, body: &[u8]) -> Option<Multipart> {
+    let boundary = format!("--{}", parameter(content_type, "boundary")?);
+    let start = parameter(content_type, "start").map(content_id);
+
+    let mut root = None;
+    let mut attachments = HashMap::new();
+    let mut position = find(body, boundary.as_bytes(), 0)? + boundary.len();
+
+    // Each part runs from the end of one boundary line to the CRLF before the next
+    while !body[position..].starts_with(b"--") {
+        let end = find(body, boundary.as_bytes(), position)?;
+        let part = &body[position..end];
+        let part = part.strip_prefix(b"\r\n").unwrap_or(part);
+        let part = part.strip_suffix(b"\r\n").unwrap_or(part);
+
+        let header_end = find(part, b"\r\n\r\n", 0)?;
+        let headers = String::from_utf8_lossy(&part[..header_end]);
+        let content = part[header_end + 4..].to_vec();
+
+        let id = headers.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case("content-id")
+                .then(|| content_id(value).to_owned())
+        });
+
+        if root.is_none() && (start.is_none() || id.as_deref() == start) {
+            root = Some(content);
+        } else if let Some(id) = id {
+            attachments.insert(id, content);
+        }
+
+        position = end + boundary.len();
+    }
+
+    Some(Multipart {
+        root: root?,
+        attachments,
+    })
+}
+
+// Attachments are sent in the order they were written, each with its content id
+type Attachment = (String, Vec<u8>);
+
+thread_local! {
+    static OUTGOING: RefCell<Option<Vec<Attachment>>> = const { RefCell::new(None) };
+}
+
+// Binary content written while this runs is collected as attachments rather than written inline
+pub fn collect<T>(write: impl FnOnce() -> T) -> (T, Vec<Attachment>) {
+    OUTGOING.with(|outgoing| *outgoing.borrow_mut() = Some(Vec::new()));
+    let result = write();
+    let attachments = OUTGOING.with(|outgoing| outgoing.borrow_mut().take()).unwrap_or_default();
+
+    (result, attachments)
+}
+
+pub(crate) fn attach(bytes: &[u8]) -> Option<String> {
+    OUTGOING.with(|outgoing| {
+        let mut outgoing = outgoing.borrow_mut();
+        let attachments = outgoing.as_mut()?;
+        let id = format!("attachment{}@suds", attachments.len());
+        attachments.push((id.clone(), bytes.to_vec()));
+
+        Some(id)
+    })
+}
+
+// Returns the content type and body of a multipart/related message with the envelope as its root
+pub fn build(root: &[u8], root_type: &str, attachments: &[Attachment]) -> (String, Vec<u8>) {
+    let contents = || std::iter::once(root).chain(attachments.iter().map(|(_, content)| content.as_slice()));
+
+    let mut index = 0;
+    let boundary = loop {
+        let boundary = format!("suds-boundary-{}", index);
+
+        if contents().all(|content| find(content, boundary.as_bytes(), 0).is_none()) {
+            break boundary;
+        }
+
+        index += 1;
+    };
+
+    let mime_type = root_type.split(';').next().unwrap_or_default().trim();
+    let content_type = format!(
+        "multipart/related; type=\"application/xop+xml\"; start=\"<{}>\"; start-info=\"{}\"; boundary=\"{}\"",
+        ROOT_ID, mime_type, boundary
+    );
+
+    let mut body = Vec::new();
+    let root_headers = format!(
+        "Content-Type: application/xop+xml; charset=UTF-8; type=\"{}\"\r\nContent-Transfer-Encoding: 8bit\r\nContent-ID: <{}>",
+        root_type.replace('"', "\\\""),
+        ROOT_ID
+    );
+
+    write_part(&mut body, &boundary, &root_headers, root);
+
+    for (id, content) in attachments {
+        let headers = format!(
+            "Content-Type: application/octet-stream\r\nContent-Transfer-Encoding: binary\r\nContent-ID: <{}>",
+            id
+        );
+
+        write_part(&mut body, &boundary, &headers, content);
+    }
+
+    body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+
+    (content_type, body)
+}
+
+const ROOT_ID: &str = "root@suds";
+
+fn write_part(body: &mut Vec<u8>, boundary: &str, headers: &str, content: &[u8]) {
+    body.extend_from_slice(format!("--{}\r\n{}\r\n\r\n", boundary, headers).as_bytes());
+    body.extend_from_slice(content);
+    body.extend_from_slice(b"\r\n");
+}
@@ -0,0 +1,211 @@
+use super::xml::{
+    events::{BytesStart, BytesText, Event},
+    ToXml, Writer,
+};
+
+use sha1::{Digest, Sha1};
+use std::{
+    io::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const WSSE_NAMESPACE: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-secext-1.0.xsd";
+const WSU_NAMESPACE: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-utility-1.0.xsd";
+const TOKEN_PROFILE: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-username-token-profile-1.0";
+const BASE64_ENCODING: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-soap-message-security-1.0#Base64Binary";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordType {
+    Text,
+    Digest,
+}
+
+#[derive(Debug, Clone)]
+pub struct SecurityHeader {
+    username: String,
+    password: String,
+    password_type: PasswordType,
+    nonce: Vec<u8>,
+    created: String,
+}
+
+impl SecurityHeader {
+    pub fn new(username: &str, password: &str, password_type: PasswordType) -> Self {
+        Self {
+            username: username.to_owned(),
+            password: password.to_owned(),
+            password_type,
+            nonce: rand::random::<[u8; 16]>().to_vec(),
+            created: timestamp(SystemTime::now()),
+        }
+    }
+
+    pub fn password_text(username: &str, password: &str) -> Self {
+        Self::new(username, password, PasswordType::Text)
+    }
+
+    pub fn password_digest(username: &str, password: &str) -> Self {
+        Self::new(username, password, PasswordType::Digest)
+    }
+
+    pub fn with_nonce(mut self, nonce: &[u8]) -> Self {
+        self.nonce = nonce.to_vec();
+        self
+    }
+
+    pub fn with_created(mut self, created: &str) -> Self {
+        self.created = created.to_owned();
+        self
+    }
+
+    // Servers reject a nonce they have already seen, so each request needs a fresh one
+    pub fn renewed(&self) -> Self {
+        Self::new(&self.username, &self.password, self.password_type)
+    }
+
+    fn password(&self) -> String {
+        match self.password_type {
+            PasswordType::Text => self.password.clone(),
+            PasswordType::Digest => {
+                let mut hasher = Sha1::new();
+                hasher.update(&self.nonce);
+                hasher.update(self.created.as_bytes());
+                hasher.update(self.password.as_bytes());
+                base64::encode(hasher.finalize())
+            }
+        }
+    }
+}
+
+fn timestamp(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let (days, seconds) = ((seconds / 86400) as i64, seconds % 86400);
+
+    // Civil date from days since the epoch, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+fn write_text_element<W: Write>(writer: &mut Writer<W>, start: BytesStart, text: &str) {
+    writer.write_event(Event::Start(start.to_borrowed())).unwrap();
+    writer
+        .write_event(Event::Text(BytesText::from_plain_str(text)))
+        .unwrap();
+    writer.write_event(Event::End(start.to_end())).unwrap();
+}
+
+impl ToXml for SecurityHeader {
+    fn to_xml<W: Write>(&self, writer: &mut Writer<W>, _: bool) {
+        let security = BytesStart::owned_name("wsse:Security")
+            .with_attributes([("xmlns:wsse", WSSE_NAMESPACE), ("xmlns:wsu", WSU_NAMESPACE)]);
+        let token = BytesStart::owned_name("wsse:UsernameToken");
+
+        let password_type = match self.password_type {
+            PasswordType::Text => format!("{}#PasswordText", TOKEN_PROFILE),
+            PasswordType::Digest => format!("{}#PasswordDigest", TOKEN_PROFILE),
+        };
+
+        writer
+            .write_event(Event::Start(security.to_borrowed()))
+            .unwrap();
+        writer.write_event(Event::Start(token.to_borrowed())).unwrap();
+
+        write_text_element(
+            writer,
+            BytesStart::owned_name("wsse:Username"),
+            &self.username,
+        );
+        write_text_element(
+            writer,
+            BytesStart::owned_name("wsse:Password")
+                .with_attributes([("Type", password_type.as_str())]),
+            &self.password(),
+        );
+        write_text_element(
+            writer,
+            BytesStart::owned_name("wsse:Nonce").with_attributes([("EncodingType", BASE64_ENCODING)]),
+            &base64::encode(&self.nonce),
+        );
+        write_text_element(writer, BytesStart::owned_name("wsu:Created"), &self.created);
+
+        writer.write_event(Event::End(token.to_end())).unwrap();
+        writer.write_event(Event::End(security.to_end())).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{io::Cursor, time::Duration};
+
+    fn to_string(header: &SecurityHeader) -> String {
+        let mut writer = Writer::new(Cursor::new(Vec::new()));
+        header.to_xml(&mut writer, false);
+        String::from_utf8(writer.into_inner().into_inner()).unwrap()
+    }
+
+    #[test]
+    fn password_digest_fixture() {
+        let header = SecurityHeader::password_digest("user", "secret")
+            .with_nonce(b"0123456789abcdef")
+            .with_created("2021-10-01T12:00:00Z");
+
+        let expected = concat!(
+            r#"<wsse:Security xmlns:wsse="http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-secext-1.0.xsd" xmlns:wsu="http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-utility-1.0.xsd">"#,
+            r#"<wsse:UsernameToken>"#,
+            r#"<wsse:Username>user</wsse:Username>"#,
+            r#"<wsse:Password Type="http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-username-token-profile-1.0#PasswordDigest">YUqeyrkLVJ2kObNEexLb2Zz5ess=</wsse:Password>"#,
+            r#"<wsse:Nonce EncodingType="http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-soap-message-security-1.0#Base64Binary">MDEyMzQ1Njc4OWFiY2RlZg==</wsse:Nonce>"#,
+            r#"<wsu:Created>2021-10-01T12:00:00Z</wsu:Created>"#,
+            r#"</wsse:UsernameToken>"#,
+            r#"</wsse:Security>"#,
+        );
+
+        assert_eq!(to_string(&header), expected);
+    }
+
+    #[test]
+    fn password_text_is_sent_as_is() {
+        let header = SecurityHeader::password_text("user", "secret");
+        assert!(to_string(&header).contains(
+            r#"#PasswordText">secret</wsse:Password>"#
+        ));
+    }
+
+    #[test]
+    fn renewed_header_has_a_fresh_nonce() {
+        let header = SecurityHeader::password_digest("user", "secret").with_nonce(b"0123456789abcdef");
+        let renewed = header.renewed();
+
+        assert_eq!(renewed.username, "user");
+        assert_eq!(renewed.password_type, PasswordType::Digest);
+        assert_ne!(renewed.nonce, header.nonce);
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(timestamp(UNIX_EPOCH + Duration::from_secs(1633089600)), "2021-10-01T12:00:00Z");
+        assert_eq!(timestamp(UNIX_EPOCH + Duration::from_secs(951782400)), "2000-02-29T00:00:00Z");
+    }
+}
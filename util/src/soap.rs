@@ -1,46 +1,575 @@
-use super::xml::{
-    events::{BytesStart, Event},
-    expect_end, expect_start, FromXml, Reader, ToXml, Writer,
+use super::{
+    error::{SoapError, XmlError},
+    mtom::{self, Multipart},
+    security::SecurityHeader,
+    xml::{
+        events::{BytesStart, Event},
+        expect_any_start, expect_end, expect_start, expect_value, is_next_any_start,
+        is_next_start, read_text, read_xml, AnyXml, FromXml, Reader, ToXml, Writer,
+    },
 };
 
-use bytes::Buf;
-use reqwest::blocking::Client as Reqwest;
-use std::io::{BufRead, BufReader, Cursor, Read, Write};
+pub use reqwest::{
+    blocking::Client as Reqwest,
+    header::{HeaderMap, HeaderValue, IntoHeaderName},
+    redirect::Policy as RedirectPolicy,
+    Proxy, StatusCode,
+};
+
+#[cfg(feature = "tls-client-auth")]
+pub use reqwest::Identity;
+
+use bytes::Bytes;
+use flate2::{write::GzEncoder, Compression};
+use reqwest::blocking::Response;
+use std::{
+    io::{BufRead, BufReader, Cursor, Read, Write},
+    marker::PhantomData,
+    time::Duration,
+};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SoapVersion {
+    #[default]
+    Soap11,
+    Soap12,
+}
+
+#[derive(Clone)]
 pub struct Client {
     client: Reqwest,
-    url: &'static str,
+    url: String,
+    version: SoapVersion,
+    basic_auth: Option<(String, String)>,
+    security: Option<SecurityHeader>,
+    headers: HeaderMap,
+    compress_requests: bool,
+    validate: bool,
+    mtom: bool,
+}
+
+pub struct ClientBuilder {
+    builder: reqwest::blocking::ClientBuilder,
+    url: String,
+    version: SoapVersion,
+    basic_auth: Option<(String, String)>,
+    security: Option<SecurityHeader>,
+    headers: HeaderMap,
+    compress_requests: bool,
+    validate: bool,
+    mtom: bool,
 }
 
 #[derive(Debug)]
-pub struct Envelope<T> {
+pub struct Envelope<T, H = ()> {
     body: T,
+    header: Option<H>,
+    security: Option<SecurityHeader>,
+    version: SoapVersion,
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcBody<T> {
+    name: String,
+    namespace: String,
+    body: T,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResponseMeta {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+}
+
+pub struct ResponseStream<U, R: Read = Response> {
+    reader: Reader<BufReader<R>>,
+    buffer: Vec<u8>,
+    finished: bool,
+    item: PhantomData<U>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SoapFault {
+    pub fault_code: String,
+    pub fault_string: String,
+    pub fault_actor: Option<String>,
+    pub detail: Option<String>,
+}
+
+impl SoapVersion {
+    pub fn namespace(&self) -> &'static str {
+        match self {
+            Self::Soap11 => "http://schemas.xmlsoap.org/soap/envelope/",
+            Self::Soap12 => "http://www.w3.org/2003/05/soap-envelope",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Soap11 => "text/xml",
+            Self::Soap12 => "application/soap+xml",
+        }
+    }
 }
 
 impl Client {
-    pub fn new(url: &'static str) -> Self {
+    pub fn new(url: &str) -> Self {
+        Self::from_reqwest(url, Reqwest::new())
+    }
+
+    // The given client is used as is, so any settings it was built with are kept
+    pub fn from_reqwest(url: &str, client: Reqwest) -> Self {
+        Self {
+            client,
+            url: url.to_owned(),
+            version: SoapVersion::default(),
+            basic_auth: None,
+            security: None,
+            headers: HeaderMap::new(),
+            compress_requests: false,
+            validate: false,
+            mtom: false,
+        }
+    }
+
+    pub fn builder(url: &str) -> ClientBuilder {
+        ClientBuilder {
+            builder: Reqwest::builder(),
+            url: url.to_owned(),
+            version: SoapVersion::default(),
+            basic_auth: None,
+            security: None,
+            headers: HeaderMap::new(),
+            compress_requests: false,
+            validate: false,
+            mtom: false,
+        }
+    }
+
+    pub fn with_basic_auth(url: &str, username: &str, password: &str) -> Self {
         Self {
-            client: Reqwest::new(),
-            url,
+            basic_auth: Some((username.to_owned(), password.to_owned())),
+            ..Self::new(url)
+        }
+    }
+
+    pub fn with_timeout(url: &str, timeout: Duration) -> Result<Self, SoapError> {
+        Self::builder(url).timeout(timeout).build()
+    }
+
+    pub fn with_proxy(url: &str, proxy: Proxy) -> Result<Self, SoapError> {
+        Self::builder(url).proxy(proxy).build()
+    }
+
+    // Without this reqwest follows up to ten redirects, 301 and 302 resend the request as a GET
+    pub fn with_redirect_policy(url: &str, policy: RedirectPolicy) -> Result<Self, SoapError> {
+        Self::builder(url).redirect(policy).build()
+    }
+
+    #[cfg(feature = "tls-client-auth")]
+    pub fn with_identity(url: &str, identity: Identity) -> Result<Self, SoapError> {
+        Self::builder(url).identity(identity).build()
+    }
+
+    // Disables certificate validation entirely, only ever use this against test servers
+    pub fn danger_accept_invalid_certs(url: &str, accept: bool) -> Result<Self, SoapError> {
+        Self::builder(url).danger_accept_invalid_certs(accept).build()
+    }
+
+    pub fn with_cookies(url: &str) -> Result<Self, SoapError> {
+        Self::builder(url).cookie_store(true).build()
+    }
+
+    pub fn with_gzip(url: &str) -> Result<Self, SoapError> {
+        Self::builder(url).gzip(true).build()
+    }
+
+    pub fn with_mtom(url: &str) -> Self {
+        Self {
+            mtom: true,
+            ..Self::new(url)
+        }
+    }
+
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.url = url.to_owned();
+        self
+    }
+
+    pub fn with_version(mut self, version: SoapVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    // Sent with every request that doesn't carry its own, with a fresh nonce and timestamp each time
+    pub fn with_security(mut self, security: SecurityHeader) -> Self {
+        self.security = Some(security);
+        self
+    }
+
+    // Restricted values in responses are checked against their facets, failing with a validation error
+    pub fn with_validation(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    pub fn with_header<K: IntoHeaderName>(mut self, name: K, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    pub fn send<T: ToXml, H: ToXml, U: FromXml>(
+        &self,
+        action: &str,
+        request_envelope: Envelope<T, H>,
+    ) -> Result<Envelope<U>, SoapError> {
+        let (envelope, _) = self.send_with_meta(action, request_envelope)?;
+        Ok(envelope)
+    }
+
+    // The headers are only sent with this request, on top of those set on the client
+    pub fn send_with_headers<T: ToXml, H: ToXml, U: FromXml>(
+        &self,
+        action: &str,
+        request_envelope: Envelope<T, H>,
+        headers: HeaderMap,
+    ) -> Result<Envelope<U>, SoapError> {
+        let response = self.execute(action, request_envelope, headers)?;
+        let (envelope, _) = self.receive(response)?;
+        Ok(envelope)
+    }
+
+    // One-way operations have no response to read, though a fault can still be sent in its place
+    pub fn send_one_way<T: ToXml, H: ToXml>(
+        &self,
+        action: &str,
+        request_envelope: Envelope<T, H>,
+    ) -> Result<(), SoapError> {
+        let response = self.execute(action, request_envelope, HeaderMap::new())?;
+        Self::acknowledge(response)
+    }
+
+    pub fn send_one_way_with_headers<T: ToXml, H: ToXml>(
+        &self,
+        action: &str,
+        request_envelope: Envelope<T, H>,
+        headers: HeaderMap,
+    ) -> Result<(), SoapError> {
+        let response = self.execute(action, request_envelope, headers)?;
+        Self::acknowledge(response)
+    }
+
+    fn acknowledge(response: Response) -> Result<(), SoapError> {
+        let status = response.status();
+
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let body = response.bytes().map_err(SoapError::BodyError)?;
+        Envelope::<AnyXml>::from_response(&body[..])?;
+
+        Err(SoapError::StatusError {
+            status,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        })
+    }
+
+    pub fn send_with_meta<T: ToXml, H: ToXml, U: FromXml>(
+        &self,
+        action: &str,
+        request_envelope: Envelope<T, H>,
+    ) -> Result<(Envelope<U>, ResponseMeta), SoapError> {
+        let response = self.execute(action, request_envelope, HeaderMap::new())?;
+        self.receive(response)
+    }
+
+    fn receive<U: FromXml>(&self, response: Response) -> Result<(Envelope<U>, ResponseMeta), SoapError> {
+        let meta = ResponseMeta {
+            status: response.status(),
+            headers: response.headers().clone(),
+        };
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .unwrap_or_default()
+            .to_owned();
+
+        let body = response.bytes().map_err(SoapError::BodyError)?;
+
+        let result = if mtom::is_multipart(&content_type) {
+            let multipart = mtom::parse(&content_type, &body).ok_or(SoapError::MultipartError)?;
+            let mut reader = Reader::from_reader(Cursor::new(multipart.root));
+            reader.attachments(multipart.attachments).validate(self.validate);
+            Envelope::<U>::from_reader(reader)
+        } else {
+            let mut reader = Reader::from_reader(&body[..]);
+            reader.validate(self.validate);
+            Envelope::<U>::from_reader(reader)
+        };
+
+        match result {
+            Ok(envelope) => Ok((envelope, meta)),
+            Err(SoapError::Deserialize { source, .. }) => Err(SoapError::Deserialize {
+                body: String::from_utf8_lossy(&body).into_owned(),
+                source,
+            }),
+            Err(error) => Err(error),
         }
     }
 
-    pub fn send<T: ToXml, U: FromXml>(&self, request_envelope: Envelope<T>) -> Envelope<U> {
-        let response = self
+    pub fn send_raw<T: ToXml, H: ToXml>(
+        &self,
+        action: &str,
+        request_envelope: Envelope<T, H>,
+    ) -> Result<String, SoapError> {
+        let body = self.post(action, request_envelope)?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
+    pub fn send_streaming<T: ToXml, H: ToXml, U: FromXml>(
+        &self,
+        action: &str,
+        request_envelope: Envelope<T, H>,
+    ) -> Result<ResponseStream<U>, SoapError> {
+        let response = self.execute(action, request_envelope, HeaderMap::new())?;
+        ResponseStream::from_response(response)
+    }
+
+    fn post<T: ToXml, H: ToXml>(
+        &self,
+        action: &str,
+        request_envelope: Envelope<T, H>,
+    ) -> Result<Bytes, SoapError> {
+        let response = self.execute(action, request_envelope, HeaderMap::new())?;
+        response.bytes().map_err(SoapError::BodyError)
+    }
+
+    fn execute<T: ToXml, H: ToXml>(
+        &self,
+        action: &str,
+        request_envelope: Envelope<T, H>,
+        headers: HeaderMap,
+    ) -> Result<Response, SoapError> {
+        let request_envelope = match &self.security {
+            Some(security) if request_envelope.security.is_none() => {
+                request_envelope.with_security(security.renewed())
+            }
+            _ => request_envelope,
+        };
+
+        let request_envelope = request_envelope.with_version(self.version);
+
+        // SOAP 1.2 carries the action as a content type parameter rather than a separate header
+        let content_type = match self.version {
+            SoapVersion::Soap12 if !action.is_empty() => {
+                format!("{}; action=\"{}\"", self.version.content_type(), action)
+            }
+            _ => self.version.content_type().to_owned(),
+        };
+
+        let (content_type, body) = if self.mtom {
+            match mtom::collect(|| request_envelope.to_request_compact()) {
+                (body, attachments) if attachments.is_empty() => (content_type, body),
+                (body, attachments) => mtom::build(&body, &content_type, &attachments),
+            }
+        } else {
+            (content_type, request_envelope.to_request_compact())
+        };
+
+        let mut request_headers = self.headers.clone();
+        request_headers.extend(headers);
+
+        let request = self
             .client
-            .post(self.url)
-            .body(request_envelope.to_request())
-            .header(reqwest::header::CONTENT_TYPE, "text/xml")
-            .send()
-            .unwrap();
+            .post(&self.url)
+            .headers(request_headers)
+            .header(reqwest::header::CONTENT_TYPE, content_type);
+
+        let mut request = match self.version {
+            SoapVersion::Soap11 => request.header("SOAPAction", format!("\"{}\"", action)),
+            SoapVersion::Soap12 => request,
+        };
 
-        Envelope::<U>::from_response(response.bytes().unwrap().reader())
+        request = if self.compress_requests {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&body).map_err(SoapError::CompressionError)?;
+
+            request
+                .header(reqwest::header::CONTENT_ENCODING, "gzip")
+                .body(encoder.finish().map_err(SoapError::CompressionError)?)
+        } else {
+            request.body(body)
+        };
+
+        if let Some((username, password)) = &self.basic_auth {
+            request = request.basic_auth(username, Some(password));
+        }
+
+        let response = request.send()?;
+
+        let status = response.status();
+        let is_xml = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .is_some_and(|content_type| content_type.contains("xml"));
+
+        // SOAP faults are reported with a 500 status, so those bodies still need parsing
+        if !status.is_success() && (status != reqwest::StatusCode::INTERNAL_SERVER_ERROR || !is_xml) {
+            // The body often explains the status, so it is kept for diagnosing the failure
+            let body = response.bytes().map_err(SoapError::BodyError)?;
+
+            return Err(SoapError::StatusError {
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            });
+        }
+
+        Ok(response)
+    }
+}
+
+impl ClientBuilder {
+    pub fn version(mut self, version: SoapVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn basic_auth(mut self, username: &str, password: &str) -> Self {
+        self.basic_auth = Some((username.to_owned(), password.to_owned()));
+        self
+    }
+
+    pub fn security(mut self, security: SecurityHeader) -> Self {
+        self.security = Some(security);
+        self
+    }
+
+    pub fn header<K: IntoHeaderName>(mut self, name: K, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.builder = self.builder.timeout(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.builder = self.builder.connect_timeout(timeout);
+        self
+    }
+
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.builder = self.builder.proxy(proxy);
+        self
+    }
+
+    pub fn redirect(mut self, policy: RedirectPolicy) -> Self {
+        self.builder = self.builder.redirect(policy);
+        self
+    }
+
+    #[cfg(feature = "tls-client-auth")]
+    pub fn identity(mut self, identity: Identity) -> Self {
+        self.builder = self.builder.identity(identity);
+        self
+    }
+
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.builder = self.builder.danger_accept_invalid_certs(accept);
+        self
+    }
+
+    pub fn cookie_store(mut self, enable: bool) -> Self {
+        self.builder = self.builder.cookie_store(enable);
+        self
+    }
+
+    // Asks for gzip responses and decodes them, requests are still sent as they are
+    pub fn gzip(mut self, enable: bool) -> Self {
+        self.builder = self.builder.gzip(enable);
+        self
+    }
+
+    // Not every server accepts a compressed request, so this is separate from gzip
+    pub fn compress_requests(mut self, enable: bool) -> Self {
+        self.compress_requests = enable;
+        self
+    }
+
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    // Binary content is sent as multipart/related attachments rather than inline base64
+    pub fn mtom(mut self, enable: bool) -> Self {
+        self.mtom = enable;
+        self
+    }
+
+    pub fn build(self) -> Result<Client, SoapError> {
+        Ok(Client {
+            client: self.builder.build().map_err(SoapError::BuildError)?,
+            url: self.url,
+            version: self.version,
+            basic_auth: self.basic_auth,
+            security: self.security,
+            headers: self.headers,
+            compress_requests: self.compress_requests,
+            validate: self.validate,
+            mtom: self.mtom,
+        })
     }
 }
 
 impl<T> Envelope<T> {
     pub fn new(body: T) -> Self {
-        Self { body }
+        Self {
+            body,
+            header: None,
+            security: None,
+            version: SoapVersion::default(),
+        }
+    }
+}
+
+impl<T, H> Envelope<T, H> {
+    pub fn with_header<U>(self, header: U) -> Envelope<T, U> {
+        Envelope {
+            body: self.body,
+            header: Some(header),
+            security: self.security,
+            version: self.version,
+        }
+    }
+
+    pub fn with_version(mut self, version: SoapVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn with_security(mut self, security: SecurityHeader) -> Self {
+        self.security = Some(security);
+        self
+    }
+
+    pub fn header(&self) -> Option<&H> {
+        self.header.as_ref()
     }
 
     pub fn into_body(self) -> T {
@@ -48,33 +577,161 @@ impl<T> Envelope<T> {
     }
 }
 
-impl<T: ToXml> Envelope<T> {
+impl<T> RpcBody<T> {
+    pub fn new(name: &str, namespace: &str, body: T) -> Self {
+        Self {
+            name: name.to_owned(),
+            namespace: namespace.to_owned(),
+            body,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn into_inner(self) -> T {
+        self.body
+    }
+}
+
+impl<U: FromXml, R: Read> ResponseStream<U, R> {
+    // Items are read lazily from inside the body's first element, rather than deserializing it whole
+    pub fn from_response(read: R) -> Result<Self, SoapError> {
+        let mut reader = Reader::from_reader(BufReader::new(read));
+        reader.trim_text(true);
+        reader.expand_empty_elements(true);
+        reader.skip_unknown(true);
+        let mut buffer = Vec::new();
+
+        expect_start(&mut reader, &mut buffer, "Envelope")?;
+        expect_start(&mut reader, &mut buffer, "Body")?;
+
+        if is_next_start(&mut reader, &mut buffer, "Fault")? {
+            let fault = SoapFault::from_xml(&mut reader, &mut buffer)?;
+            return Err(SoapError::Fault(fault));
+        }
+
+        expect_any_start(&mut reader, &mut buffer)?;
+
+        Ok(Self {
+            reader,
+            buffer,
+            finished: false,
+            item: PhantomData,
+        })
+    }
+
+    pub fn descend(mut self) -> Result<Self, SoapError> {
+        expect_any_start(&mut self.reader, &mut self.buffer)?;
+        Ok(self)
+    }
+}
+
+impl<U: FromXml, R: Read> Iterator for ResponseStream<U, R> {
+    type Item = Result<U, SoapError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let item = match is_next_any_start(&mut self.reader, &mut self.buffer) {
+            Ok(true) => U::from_xml(&mut self.reader, &mut self.buffer),
+            Ok(false) => {
+                self.finished = true;
+                return None;
+            }
+            Err(error) => Err(error),
+        };
+
+        if item.is_err() {
+            self.finished = true;
+        }
+
+        Some(item.map_err(SoapError::from))
+    }
+}
+
+impl<T: ToXml, H: ToXml> Envelope<T, H> {
     pub fn to_request(&self) -> Vec<u8> {
         let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
         self.to_xml(&mut writer, true);
         writer.into_inner().into_inner()
     }
+
+    pub fn to_request_compact(&self) -> Vec<u8> {
+        let mut writer = Writer::new(Cursor::new(Vec::new()));
+        self.to_xml(&mut writer, true);
+        writer.into_inner().into_inner()
+    }
 }
 
 impl<T: FromXml> Envelope<T> {
-    pub fn from_response<R: Read>(read: R) -> Self {
-        let mut reader = Reader::from_reader(BufReader::new(read));
+    pub fn from_response<R: Read>(read: R) -> Result<Self, SoapError> {
+        Self::from_reader(Reader::from_reader(BufReader::new(read)))
+    }
+
+    pub fn from_multipart(multipart: Multipart) -> Result<Self, SoapError> {
+        let mut reader = Reader::from_reader(Cursor::new(multipart.root));
+        reader.attachments(multipart.attachments);
+        Self::from_reader(reader)
+    }
+
+    fn from_reader<R: BufRead>(mut reader: Reader<R>) -> Result<Self, SoapError> {
         reader.trim_text(true);
         reader.expand_empty_elements(true);
+        reader.skip_unknown(true);
         let mut buffer = Vec::new();
-        Self::from_xml(&mut reader, &mut buffer)
+
+        expect_start(&mut reader, &mut buffer, "Envelope")?;
+        expect_start(&mut reader, &mut buffer, "Body")?;
+
+        if is_next_start(&mut reader, &mut buffer, "Fault")? {
+            let fault = SoapFault::from_xml(&mut reader, &mut buffer)?;
+            return Err(SoapError::Fault(fault));
+        }
+
+        let body = T::from_xml(&mut reader, &mut buffer)?;
+        expect_end(&mut reader, &mut buffer)?;
+        expect_end(&mut reader, &mut buffer)?;
+
+        Ok(Self::new(body))
     }
 }
 
-impl<T: ToXml> ToXml for Envelope<T> {
+impl<T: ToXml, H: ToXml> ToXml for Envelope<T, H> {
     fn to_xml<W: Write>(&self, writer: &mut Writer<W>, top_level: bool) {
         let envelope = BytesStart::owned_name("soapenv:Envelope")
-            .with_attributes([("xmlns:soapenv", "http://schemas.xmlsoap.org/soap/envelope/")]);
+            .with_attributes([("xmlns:soapenv", self.version.namespace())]);
         let body = BytesStart::owned_name("soapenv:Body");
 
         writer
             .write_event(Event::Start(envelope.to_borrowed()))
             .unwrap();
+
+        if self.header.is_some() || self.security.is_some() {
+            let header = BytesStart::owned_name("soapenv:Header");
+
+            writer
+                .write_event(Event::Start(header.to_borrowed()))
+                .unwrap();
+
+            if let Some(security) = &self.security {
+                security.to_xml(writer, false);
+            }
+
+            if let Some(payload) = &self.header {
+                payload.to_xml(writer, top_level);
+            }
+
+            writer.write_event(Event::End(header.to_end())).unwrap();
+        }
+
         writer
             .write_event(Event::Start(body.to_borrowed()))
             .unwrap();
@@ -85,13 +742,872 @@ impl<T: ToXml> ToXml for Envelope<T> {
 }
 
 impl<T: FromXml> FromXml for Envelope<T> {
-    fn from_xml<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Self {
-        expect_start(reader, buffer, "Envelope").unwrap();
-        expect_start(reader, buffer, "Body").unwrap();
-        let body = T::from_xml(reader, buffer);
-        expect_end(reader, buffer).unwrap();
-        expect_end(reader, buffer).unwrap();
-
-        Self::new(body)
+    fn from_xml<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, XmlError> {
+        expect_start(reader, buffer, "Envelope")?;
+        expect_start(reader, buffer, "Body")?;
+        let body = T::from_xml(reader, buffer)?;
+        expect_end(reader, buffer)?;
+        expect_end(reader, buffer)?;
+
+        Ok(Self::new(body))
+    }
+}
+
+impl<T: ToXml> ToXml for RpcBody<T> {
+    fn to_xml<W: Write>(&self, writer: &mut Writer<W>, top_level: bool) {
+        // A prefix rather than a default namespace, so the unqualified parts inside stay unqualified
+        let start = BytesStart::owned_name(format!("rpc:{}", self.name))
+            .with_attributes([("xmlns:rpc", self.namespace.as_str())]);
+
+        writer.write_event(Event::Start(start.to_borrowed())).unwrap();
+        self.body.to_xml(writer, top_level);
+        writer.write_event(Event::End(start.to_end())).unwrap();
+    }
+}
+
+impl<T: FromXml> FromXml for RpcBody<T> {
+    fn from_xml<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, XmlError> {
+        // Response wrappers are named after the operation with a Response suffix, so any name is accepted
+        let start = expect_any_start(reader, buffer)?;
+        let name = String::from_utf8_lossy(start.local_name()).into_owned();
+        let namespace = reader
+            .resolve_namespace(&start)
+            .map(|namespace| String::from_utf8_lossy(namespace).into_owned())
+            .unwrap_or_default();
+
+        let body = T::from_xml(reader, buffer)?;
+        expect_end(reader, buffer)?;
+
+        Ok(Self {
+            name,
+            namespace,
+            body,
+        })
+    }
+}
+
+impl SoapFault {
+    // Declared faults carry their message in the detail, so this is tried against each of them in turn
+    pub fn detail_as<T: FromXml>(&self) -> Option<T> {
+        let detail = self.detail.as_ref()?;
+
+        let mut reader = Reader::from_reader(detail.as_bytes());
+        reader.trim_text(true);
+        reader.expand_empty_elements(true);
+        reader.skip_unknown(true);
+        let mut buffer = Vec::new();
+
+        T::from_xml(&mut reader, &mut buffer).ok()
+    }
+}
+
+impl FromXml for SoapFault {
+    fn from_xml<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, XmlError> {
+        expect_start(reader, buffer, "Fault")?;
+
+        let fault = if is_next_start(reader, buffer, "Code")? {
+            expect_start(reader, buffer, "Code")?;
+            expect_start(reader, buffer, "Value")?;
+            let fault_code = expect_value(reader, buffer)?;
+            expect_end(reader, buffer)?;
+            read_text(reader, buffer)?;
+
+            expect_start(reader, buffer, "Reason")?;
+            let fault_string = read_text(reader, buffer)?;
+
+            if is_next_start(reader, buffer, "Node")? {
+                expect_start(reader, buffer, "Node")?;
+                read_text(reader, buffer)?;
+            }
+
+            let fault_actor = if is_next_start(reader, buffer, "Role")? {
+                expect_start(reader, buffer, "Role")?;
+                Some(read_text(reader, buffer)?)
+            } else {
+                None
+            };
+
+            let detail = if is_next_start(reader, buffer, "Detail")? {
+                expect_start(reader, buffer, "Detail")?;
+                let detail = read_xml(reader, buffer)?;
+                expect_end(reader, buffer)?;
+                Some(detail)
+            } else {
+                None
+            };
+
+            Self {
+                fault_code,
+                fault_string,
+                fault_actor,
+                detail,
+            }
+        } else {
+            expect_start(reader, buffer, "faultcode")?;
+            let fault_code = expect_value(reader, buffer)?;
+            expect_end(reader, buffer)?;
+
+            expect_start(reader, buffer, "faultstring")?;
+            let fault_string = expect_value(reader, buffer)?;
+            expect_end(reader, buffer)?;
+
+            let fault_actor = if is_next_start(reader, buffer, "faultactor")? {
+                expect_start(reader, buffer, "faultactor")?;
+                Some(read_text(reader, buffer)?)
+            } else {
+                None
+            };
+
+            let detail = if is_next_start(reader, buffer, "detail")? {
+                expect_start(reader, buffer, "detail")?;
+                let detail = read_xml(reader, buffer)?;
+                expect_end(reader, buffer)?;
+                Some(detail)
+            } else {
+                None
+            };
+
+            Self {
+                fault_code,
+                fault_string,
+                fault_actor,
+                detail,
+            }
+        };
+
+        expect_end(reader, buffer)?;
+        Ok(fault)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xml::read_base64;
+    use std::{
+        cell::Cell,
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        rc::Rc,
+        thread,
+    };
+
+    // Answers a single request with an envelope whose body is built from the request headers and body
+    fn mock_server<F>(respond: F) -> &'static str
+    where
+        F: FnOnce(Vec<(String, String)>, String) -> String + Send + 'static,
+    {
+        raw_server(move |headers, body| {
+            let response = format!(
+                "<Envelope><Body>{}</Body></Envelope>",
+                respond(headers, String::from_utf8(body).unwrap())
+            );
+
+            (Vec::new(), response.into_bytes())
+        })
+    }
+
+    // Answers a single request with the extra headers and raw body built from the request
+    fn raw_server<F>(respond: F) -> &'static str
+    where
+        F: FnOnce(Vec<(String, String)>, Vec<u8>) -> (Vec<(&'static str, &'static str)>, Vec<u8>) + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut headers = Vec::new();
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+
+                match line.trim_end().split_once(':') {
+                    Some((key, value)) => headers.push((key.to_ascii_lowercase(), value.trim().to_owned())),
+                    None => break,
+                }
+            }
+
+            let length = headers
+                .iter()
+                .find(|(key, _)| key == "content-length")
+                .map_or(0, |(_, value)| value.parse().unwrap());
+
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+
+            let (extra_headers, response) = respond(headers, body);
+
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n",
+                response.len()
+            )
+            .unwrap();
+
+            if !extra_headers.iter().any(|(key, _)| key.eq_ignore_ascii_case("content-type")) {
+                write!(stream, "Content-Type: text/xml\r\n").unwrap();
+            }
+
+            for (key, value) in extra_headers {
+                write!(stream, "{}: {}\r\n", key, value).unwrap();
+            }
+
+            write!(stream, "\r\n").unwrap();
+            stream.write_all(&response).unwrap();
+        });
+
+        Box::leak(url.into_boxed_str())
+    }
+
+    // Answers a single request with the given status, extra headers and body
+    fn status_server(status: &'static str, headers: String, body: &'static str) -> &'static str {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut line = String::new();
+            while line != "\r\n" {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+            }
+
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                headers,
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+
+        Box::leak(url.into_boxed_str())
+    }
+
+    // Redirects a single request to the given location, keeping its method and body
+    fn redirect_server(location: &'static str) -> &'static str {
+        status_server("307 Temporary Redirect", format!("Location: {}\r\n", location), "")
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Blob(Vec<u8>);
+
+    impl ToXml for Blob {
+        fn to_xml<W: Write>(&self, writer: &mut Writer<W>, _: bool) {
+            let start = BytesStart::borrowed_name(b"Blob");
+            writer.write_event(Event::Start(start.to_borrowed())).unwrap();
+            crate::xml::write_base64(writer, &self.0);
+            writer.write_event(Event::End(start.to_end())).unwrap();
+        }
+    }
+
+    impl FromXml for Blob {
+        fn from_xml<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, XmlError> {
+            expect_start(reader, buffer, "Blob")?;
+            Ok(Self(crate::xml::read_base64(reader, buffer)?))
+        }
+    }
+
+    fn echo_header(name: &'static str) -> &'static str {
+        mock_server(move |headers, _| {
+            headers
+                .into_iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value)
+                .unwrap_or_default()
+        })
+    }
+
+    #[test]
+    fn sends_the_soap_action_header() {
+        let client = Client::new(echo_header("soapaction"));
+        let response: Envelope<String> = client.send("urn:ping", Envelope::new(String::new())).unwrap();
+
+        assert_eq!(response.into_body(), "\"urn:ping\"");
+    }
+
+    struct Session(&'static str);
+
+    impl ToXml for Session {
+        fn to_xml<W: Write>(&self, writer: &mut Writer<W>, _: bool) {
+            let start = BytesStart::borrowed_name(b"Session");
+            writer.write_event(Event::Start(start.to_borrowed())).unwrap();
+            self.0.to_owned().to_xml(writer, false);
+            writer.write_event(Event::End(start.to_end())).unwrap();
+        }
+    }
+
+    #[test]
+    fn header_is_written_before_the_body() {
+        let envelope = Envelope::new("body".to_owned()).with_header(Session("abc"));
+        let request = String::from_utf8(envelope.to_request()).unwrap();
+
+        let header = request.find("<soapenv:Header>").unwrap();
+        let session = request.find("<Session>abc</Session>").unwrap();
+        let body = request.find("<soapenv:Body>").unwrap();
+
+        assert!(header < session && session < body, "{}", request);
+    }
+
+    #[test]
+    fn rpc_parts_stay_unqualified() {
+        let mut writer = Writer::new(Vec::new());
+        RpcBody::new("ping", "urn:operations", Session("abc")).to_xml(&mut writer, true);
+        let written = writer.into_inner();
+
+        let mut reader = Reader::from_reader(&written[..]);
+        reader.trim_text(true);
+        let mut buffer = Vec::new();
+
+        let wrapper = expect_any_start(&mut reader, &mut buffer).unwrap();
+        assert_eq!(reader.resolve_namespace(&wrapper), Some(&b"urn:operations"[..]));
+
+        let part = expect_any_start(&mut reader, &mut buffer).unwrap();
+        assert_eq!(part.local_name(), b"Session");
+        assert_eq!(reader.resolve_namespace(&part), None);
+    }
+
+    #[test]
+    fn soap11_fault() {
+        let response = r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+            <soapenv:Body>
+                <soapenv:Fault>
+                    <faultcode>soapenv:Server</faultcode>
+                    <faultstring>Station not found</faultstring>
+                    <detail><code>404</code></detail>
+                </soapenv:Fault>
+            </soapenv:Body>
+        </soapenv:Envelope>"#;
+
+        match Envelope::<String>::from_response(response.as_bytes()) {
+            Err(SoapError::Fault(fault)) => {
+                assert_eq!(fault.fault_code, "soapenv:Server");
+                assert_eq!(fault.fault_string, "Station not found");
+                assert!(fault.detail.unwrap().contains("404"));
+            }
+            other => panic!("expected a fault, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn sends_basic_auth_credentials() {
+        let client = Client::with_basic_auth(echo_header("authorization"), "user", "secret");
+        let response: Envelope<String> = client.send("urn:ping", Envelope::new(String::new())).unwrap();
+
+        assert_eq!(response.into_body(), "Basic dXNlcjpzZWNyZXQ=");
+    }
+
+    #[test]
+    fn requests_time_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        // Accepts the connection but never answers it
+        thread::spawn(move || {
+            let (_stream, _) = listener.accept().unwrap();
+            thread::sleep(Duration::from_secs(5));
+        });
+
+        let client = Client::with_timeout(Box::leak(url.into_boxed_str()), Duration::from_millis(100)).unwrap();
+
+        match client.send::<_, (), String>("urn:ping", Envelope::new(String::new())) {
+            Err(SoapError::RequestError(error)) => assert!(error.is_timeout()),
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn sends_custom_headers() {
+        let client = Client::new(echo_header("x-api-key")).with_header("x-api-key", HeaderValue::from_static("abc123"));
+        let response: Envelope<String> = client.send("urn:ping", Envelope::new(String::new())).unwrap();
+
+        assert_eq!(response.into_body(), "abc123");
+    }
+
+    #[test]
+    fn request_headers_override_client_headers() {
+        let client = Client::new(echo_header("x-api-key")).with_header("x-api-key", HeaderValue::from_static("client"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", HeaderValue::from_static("request"));
+
+        let response: Envelope<String> =
+            client.send_with_headers("urn:ping", Envelope::new(String::new()), headers).unwrap();
+        assert_eq!(response.into_body(), "request");
+    }
+
+    #[test]
+    fn soap12_envelope() {
+        let request = Envelope::new("body".to_owned()).with_version(SoapVersion::Soap12).to_request();
+        let request = String::from_utf8(request).unwrap();
+        assert!(request.contains(r#"xmlns:soapenv="http://www.w3.org/2003/05/soap-envelope""#), "{}", request);
+
+        let client = Client::new(echo_header("content-type")).with_version(SoapVersion::Soap12);
+        let response: Envelope<String> = client.send("urn:ping", Envelope::new(String::new())).unwrap();
+
+        assert_eq!(response.into_body(), r#"application/soap+xml; action="urn:ping""#);
+    }
+
+    #[test]
+    fn soap12_actions_are_content_type_parameters() {
+        let client = Client::new(echo_header("soapaction")).with_version(SoapVersion::Soap12);
+        let response: Envelope<String> = client.send("urn:ping", Envelope::new(String::new())).unwrap();
+        assert_eq!(response.into_body(), "");
+
+        let client = Client::new(echo_header("content-type")).with_version(SoapVersion::Soap12);
+        let response: Envelope<String> = client.send("", Envelope::new(String::new())).unwrap();
+        assert_eq!(response.into_body(), "application/soap+xml");
+    }
+
+    #[test]
+    fn gzip_responses_are_decoded() {
+        let url = raw_server(|headers, body| {
+            assert!(headers.contains(&("accept-encoding".to_owned(), "gzip".to_owned())));
+            assert!(!headers.iter().any(|(key, _)| key == "content-encoding"));
+
+            let response = if String::from_utf8(body).unwrap().contains(">ping<") { "pong" } else { "unexpected" };
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            write!(encoder, "<Envelope><Body>{}</Body></Envelope>", response).unwrap();
+
+            (vec![("Content-Encoding", "gzip")], encoder.finish().unwrap())
+        });
+
+        let client = Client::with_gzip(url).unwrap();
+        let response: Envelope<String> = client.send("urn:ping", Envelope::new("ping".to_owned())).unwrap();
+
+        assert_eq!(response.into_body(), "pong");
+    }
+
+    #[test]
+    fn requests_are_compressed_when_asked() {
+        let url = raw_server(|headers, body| {
+            assert!(headers.contains(&("content-encoding".to_owned(), "gzip".to_owned())));
+
+            let mut request = String::new();
+            flate2::read::GzDecoder::new(body.as_slice()).read_to_string(&mut request).unwrap();
+
+            let response = format!("<Envelope><Body>{}</Body></Envelope>", request.contains(">ping<"));
+            (Vec::new(), response.into_bytes())
+        });
+
+        let client = Client::builder(url).compress_requests(true).build().unwrap();
+        let response: Envelope<String> = client.send("urn:ping", Envelope::new("ping".to_owned())).unwrap();
+
+        assert_eq!(response.into_body(), "true");
+    }
+
+    #[test]
+    fn cloned_clients_share_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Box::leak(format!("http://{}", listener.local_addr().unwrap()).into_boxed_str());
+
+        // Only one connection is ever accepted, so both requests have to arrive on it
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            for count in 1..=2 {
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut length = 0;
+
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+
+                    match line.trim_end().split_once(':') {
+                        Some((key, value)) if key.eq_ignore_ascii_case("content-length") => {
+                            length = value.trim().parse().unwrap()
+                        }
+                        Some(_) => (),
+                        None => break,
+                    }
+                }
+
+                reader.read_exact(&mut vec![0; length]).unwrap();
+
+                let response = format!("<Envelope><Body>{}</Body></Envelope>", count);
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\n\r\n{}",
+                    response.len(),
+                    response
+                )
+                .unwrap();
+            }
+        });
+
+        let client = Client::with_timeout("http://unused", Duration::from_secs(2)).unwrap();
+        let first = client.clone().with_url(url);
+        let second = client.with_url(url);
+
+        let response: Envelope<String> = first.send("urn:ping", Envelope::new(String::new())).unwrap();
+        assert_eq!(response.into_body(), "1");
+
+        let response: Envelope<String> = second.send("urn:ping", Envelope::new(String::new())).unwrap();
+        assert_eq!(response.into_body(), "2");
+    }
+
+    #[test]
+    fn requests_route_through_the_proxy() {
+        let proxy = Proxy::http(echo_header("host")).unwrap();
+        let client = Client::with_proxy("http://service.invalid/endpoint", proxy).unwrap();
+        let response: Envelope<String> = client.send("urn:ping", Envelope::new(String::new())).unwrap();
+
+        assert_eq!(response.into_body(), "service.invalid");
+    }
+
+    #[test]
+    fn raw_response_bodies() {
+        let client = Client::new(mock_server(|_, _| "<Result>42</Result>".to_owned()));
+        let body = client.send_raw("urn:ping", Envelope::new(String::new())).unwrap();
+
+        assert_eq!(body, "<Envelope><Body><Result>42</Result></Body></Envelope>");
+    }
+
+    #[test]
+    fn deserialize_errors_keep_the_body() {
+        let url = raw_server(|_, _| (Vec::new(), b"<Envelope><Fault/></Envelope>".to_vec()));
+        let client = Client::new(url);
+
+        match client.send::<_, (), String>("urn:ping", Envelope::new(String::new())) {
+            Err(SoapError::Deserialize { body, .. }) => assert_eq!(body, "<Envelope><Fault/></Envelope>"),
+            other => panic!("expected a deserialize error, got {:?}", other),
+        }
+    }
+
+    fn cookie_after_login(client: Client) -> String {
+        let login = raw_server(|_, _| {
+            (
+                vec![("Set-Cookie", "session=abc123; Path=/")],
+                b"<Envelope><Body/></Envelope>".to_vec(),
+            )
+        });
+
+        let _: Envelope<String> = client
+            .clone()
+            .with_url(login)
+            .send("urn:login", Envelope::new(String::new()))
+            .unwrap();
+
+        let response: Envelope<String> = client
+            .with_url(echo_header("cookie"))
+            .send("urn:ping", Envelope::new(String::new()))
+            .unwrap();
+
+        response.into_body()
+    }
+
+    #[test]
+    fn cookies_persist_across_requests() {
+        assert_eq!(cookie_after_login(Client::with_cookies("http://unused").unwrap()), "session=abc123");
+        assert_eq!(cookie_after_login(Client::new("http://unused")), "");
+    }
+
+    #[test]
+    fn compact_requests_have_no_indentation() {
+        let envelope = Envelope::new("body".to_owned()).with_header(Session("abc"));
+        let pretty = String::from_utf8(envelope.to_request()).unwrap();
+        let compact = String::from_utf8(envelope.to_request_compact()).unwrap();
+
+        assert!(pretty.contains(">\n  <"), "{}", pretty);
+        assert!(!compact.contains('\n') && !compact.contains("> "), "{}", compact);
+        assert_eq!(pretty.lines().map(str::trim).collect::<String>(), compact);
+    }
+
+    #[test]
+    fn clients_send_compact_requests() {
+        let client = Client::new(mock_server(|_, body| body.contains('\n').to_string()));
+        let response: Envelope<String> = client.send("urn:ping", Envelope::new("ping".to_owned())).unwrap();
+
+        assert_eq!(response.into_body(), "false");
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Departure(String);
+
+    impl FromXml for Departure {
+        fn from_xml<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, XmlError> {
+            expect_start(reader, buffer, "departure")?;
+            let value = expect_value(reader, buffer)?;
+            expect_end(reader, buffer)?;
+
+            Ok(Self(value))
+        }
+    }
+
+    // Writes a response body with the given number of departures only as it is read
+    struct Departures {
+        remaining: usize,
+        pending: Vec<u8>,
+        produced: Rc<Cell<usize>>,
+    }
+
+    impl Departures {
+        fn new(count: usize, produced: Rc<Cell<usize>>) -> Self {
+            Self {
+                remaining: count,
+                pending: b"<Envelope><Body><Departures>".to_vec(),
+                produced,
+            }
+        }
+    }
+
+    impl Read for Departures {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.pending.is_empty() {
+                self.pending = match self.remaining {
+                    0 => return Ok(0),
+                    1 => b"<departure>last</departure></Departures></Body></Envelope>".to_vec(),
+                    _ => format!("<departure>{}</departure>", self.remaining).into_bytes(),
+                };
+
+                self.remaining -= 1;
+            }
+
+            let count = buf.len().min(self.pending.len());
+            buf[..count].copy_from_slice(&self.pending[..count]);
+            self.pending.drain(..count);
+            self.produced.set(self.produced.get() + count);
+
+            Ok(count)
+        }
+    }
+
+    #[test]
+    fn streamed_items_are_read_as_they_arrive() {
+        let produced = Rc::new(Cell::new(0));
+        let mut stream =
+            ResponseStream::<Departure, _>::from_response(Departures::new(100_000, produced.clone())).unwrap();
+
+        assert_eq!(stream.next().unwrap().unwrap(), Departure("100000".to_owned()));
+        assert!(produced.get() < 64 * 1024, "{} bytes read", produced.get());
+
+        let rest = stream.map(Result::unwrap).collect::<Vec<_>>();
+        assert_eq!(rest.len(), 99_999);
+        assert_eq!(rest.last(), Some(&Departure("last".to_owned())));
+    }
+
+    #[test]
+    fn streams_are_sent_through_the_client() {
+        let client = Client::new(mock_server(|_, _| {
+            "<Departures><departure>1</departure><departure>2</departure></Departures>".to_owned()
+        }));
+
+        let departures = client
+            .send_streaming::<_, _, Departure>("urn:departures", Envelope::new(String::new()))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(departures, [Departure("1".to_owned()), Departure("2".to_owned())]);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Photo(Vec<u8>);
+
+    impl FromXml for Photo {
+        fn from_xml<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, XmlError> {
+            expect_start(reader, buffer, "Photo")?;
+            expect_start(reader, buffer, "data")?;
+            let data = read_base64(reader, buffer)?;
+            expect_end(reader, buffer)?;
+
+            Ok(Self(data))
+        }
+    }
+
+    fn mtom_response(id: &'static str) -> (Vec<(&'static str, &'static str)>, Vec<u8>) {
+        let mut body = format!(
+            "--MIMEBoundary\r\nContent-Type: application/xop+xml\r\nContent-ID: <root@example.com>\r\n\r\n\
+             <Envelope><Body><Photo><data><xop:Include xmlns:xop=\"http://www.w3.org/2004/08/xop/include\" href=\"cid:{}\"/></data></Photo></Body></Envelope>\r\n\
+             --MIMEBoundary\r\nContent-Type: image/png\r\nContent-ID: <photo@example.com>\r\n\r\n",
+            id
+        )
+        .into_bytes();
+
+        body.extend_from_slice(&[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x00]);
+        body.extend_from_slice(b"\r\n--MIMEBoundary--\r\n");
+
+        let content_type = "multipart/related; type=\"application/xop+xml\"; boundary=MIMEBoundary; start=\"<root@example.com>\"";
+        (vec![("Content-Type", content_type)], body)
+    }
+
+    #[test]
+    fn mtom_attachments_are_resolved() {
+        let client = Client::new(raw_server(|_, _| mtom_response("photo@example.com")));
+        let response: Envelope<Photo> = client.send("urn:photo", Envelope::new(String::new())).unwrap();
+
+        assert_eq!(response.into_body(), Photo(vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x00]));
+
+        let client = Client::new(raw_server(|_, _| mtom_response("missing@example.com")));
+        let response = client.send::<_, _, Photo>("urn:photo", Envelope::new(String::new()));
+
+        assert!(matches!(
+            response,
+            Err(SoapError::Deserialize { source: XmlError::MissingAttachment { id, .. }, .. }) if id == "missing@example.com"
+        ));
+    }
+
+    #[test]
+    fn custom_reqwest_clients_are_used() {
+        let reqwest = Reqwest::builder().user_agent("suds-test/1.0").build().unwrap();
+        let client = Client::from_reqwest(echo_header("user-agent"), reqwest);
+        let response: Envelope<String> = client.send("urn:ping", Envelope::new(String::new())).unwrap();
+
+        assert_eq!(response.into_body(), "suds-test/1.0");
+    }
+
+    #[test]
+    fn redirects_are_followed_unless_forbidden() {
+        let client = Client::new(redirect_server(mock_server(|_, body| body.contains("ping").to_string())));
+        let response: Envelope<String> = client.send("urn:ping", Envelope::new("ping".to_owned())).unwrap();
+        assert_eq!(response.into_body(), "true");
+
+        let client = Client::with_redirect_policy(redirect_server("http://unused"), RedirectPolicy::none()).unwrap();
+        let result: Result<Envelope<String>, _> = client.send("urn:ping", Envelope::new("ping".to_owned()));
+        assert!(
+            matches!(result, Err(SoapError::StatusError { status, .. }) if status == reqwest::StatusCode::TEMPORARY_REDIRECT),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn error_statuses_keep_the_response_body() {
+        let client = Client::new(status_server("503 Service Unavailable", String::new(), "down for maintenance"));
+        let result: Result<Envelope<String>, _> = client.send("urn:ping", Envelope::new("ping".to_owned()));
+
+        assert!(
+            matches!(
+                &result,
+                Err(SoapError::StatusError { status, body })
+                    if *status == StatusCode::SERVICE_UNAVAILABLE && body == "down for maintenance"
+            ),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn response_metadata_is_kept() {
+        let client = Client::new(raw_server(|_, _| {
+            (vec![("X-Trace-Id", "trace-42")], b"<Envelope><Body>pong</Body></Envelope>".to_vec())
+        }));
+
+        let (response, meta): (Envelope<String>, _) =
+            client.send_with_meta("urn:ping", Envelope::new("ping".to_owned())).unwrap();
+
+        assert_eq!(response.into_body(), "pong");
+        assert_eq!(meta.status, StatusCode::OK);
+        assert_eq!(meta.headers["x-trace-id"], "trace-42");
+    }
+
+    #[test]
+    fn client_security_is_renewed_per_request() {
+        let url = mock_server(|_, body| {
+            let fresh = body.contains("<wsse:UsernameToken>") && !body.contains("MDEyMzQ1Njc4OWFiY2RlZg==");
+            fresh.to_string()
+        });
+
+        let security = SecurityHeader::password_digest("user", "secret").with_nonce(b"0123456789abcdef");
+        let client = Client::new(url).with_security(security);
+
+        let response: Envelope<String> = client.send("", Envelope::new(String::new())).unwrap();
+
+        assert_eq!(response.into_body(), "true");
+    }
+
+    #[test]
+    fn envelope_security_is_kept() {
+        let url = mock_server(|_, body| {
+            let kept = body.matches("<wsse:Security").count() == 1 && body.contains("<wsse:Username>envelope<");
+            kept.to_string()
+        });
+
+        let client = Client::new(url).with_security(SecurityHeader::password_text("client", "secret"));
+        let envelope = Envelope::new(String::new()).with_security(SecurityHeader::password_text("envelope", "secret"));
+
+        let response: Envelope<String> = client.send("", envelope).unwrap();
+
+        assert_eq!(response.into_body(), "true");
+    }
+
+    struct Validated(bool);
+
+    impl FromXml for Validated {
+        fn from_xml<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, XmlError> {
+            expect_start(reader, buffer, "Ping")?;
+            expect_end(reader, buffer)?;
+            Ok(Self(reader.validates()))
+        }
+    }
+
+    #[test]
+    fn responses_are_only_validated_when_asked() {
+        let client = Client::new(mock_server(|_, _| "<Ping/>".to_owned()));
+        let response: Envelope<Validated> = client.send("urn:ping", Envelope::new(String::new())).unwrap();
+        assert!(!response.into_body().0);
+
+        let client = Client::builder(mock_server(|_, _| "<Ping/>".to_owned())).validate(true).build().unwrap();
+        let response: Envelope<Validated> = client.send("urn:ping", Envelope::new(String::new())).unwrap();
+        assert!(response.into_body().0);
+    }
+
+    #[test]
+    fn binary_is_inline_without_mtom() {
+        let request = Envelope::new(Blob(b"hello".to_vec())).to_request_compact();
+
+        assert!(String::from_utf8(request).unwrap().contains("<Blob>aGVsbG8=</Blob>"));
+    }
+
+    #[test]
+    fn mtom_attachment_round_trip() {
+        let bytes = vec![0, 159, 146, 150, 255];
+        let (root, attachments) = mtom::collect(|| Envelope::new(Blob(bytes.clone())).to_request_compact());
+
+        assert_eq!(attachments.len(), 1);
+        assert!(String::from_utf8_lossy(&root).contains(r#"<Blob><xop:Include xmlns:xop="http://www.w3.org/2004/08/xop/include" href="cid:attachment0@suds"/></Blob>"#));
+
+        let (content_type, body) = mtom::build(&root, "text/xml", &attachments);
+        let multipart = mtom::parse(&content_type, &body).unwrap();
+
+        assert_eq!(multipart.root, root);
+
+        let envelope: Envelope<Blob> = Envelope::from_multipart(multipart).unwrap();
+
+        assert_eq!(envelope.into_body(), Blob(bytes));
+    }
+
+    #[test]
+    fn mtom_requests_are_multipart() {
+        let url = mock_server(|headers, body| {
+            let content_type = headers
+                .into_iter()
+                .find(|(key, _)| key == "content-type")
+                .map(|(_, value)| value)
+                .unwrap_or_default();
+
+            let multipart = mtom::parse(&content_type, body.as_bytes()).unwrap();
+            let included = String::from_utf8(multipart.root).unwrap().contains("<xop:Include")
+                && multipart.attachments.values().any(|attachment| attachment == b"attachment");
+
+            included.to_string()
+        });
+
+        let client = Client::with_mtom(url);
+        let response: Envelope<String> = client.send("", Envelope::new(Blob(b"attachment".to_vec()))).unwrap();
+
+        assert_eq!(response.into_body(), "true");
     }
 }
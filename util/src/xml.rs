@@ -1,37 +1,194 @@
 use std::{
-    fmt::Debug,
+    collections::HashMap,
     io::{BufRead, Write},
     str::FromStr,
 };
 
-pub use quick_xml::{events, Reader, Writer};
+pub use quick_xml::{events, Writer};
+
+use super::error::{ValidationError, XmlError};
+
+pub struct Reader<R: BufRead> {
+    reader: quick_xml::Reader<R>,
+    peeked: Option<events::Event<'static>>,
+    scopes: Vec<Vec<(Vec<u8>, Vec<u8>)>>,
+    skip_unknown: bool,
+    validate: bool,
+    attachments: HashMap<String, Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AnyXml(pub String);
 
 pub trait ToXml {
     fn to_xml<W: Write>(&self, writer: &mut Writer<W>, top_level: bool);
+
+    // Types written as a single element can be written under another name, such as an RPC part's
+    fn to_xml_named<W: Write>(&self, writer: &mut Writer<W>, _name: &str, top_level: bool) {
+        self.to_xml(writer, top_level)
+    }
+}
+
+pub trait FromXml: Sized {
+    fn from_xml<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, XmlError>;
+
+    fn from_xml_named<R: BufRead>(
+        reader: &mut Reader<R>,
+        buffer: &mut Vec<u8>,
+        _namespace: &str,
+        _name: &str,
+    ) -> Result<Self, XmlError> {
+        Self::from_xml(reader, buffer)
+    }
 }
 
-pub trait FromXml {
-    fn from_xml<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Self;
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationError>;
+}
+
+impl<R: BufRead> Reader<R> {
+    pub fn from_reader(reader: R) -> Self {
+        Self {
+            reader: quick_xml::Reader::from_reader(reader),
+            peeked: None,
+            scopes: Vec::new(),
+            skip_unknown: false,
+            validate: false,
+            attachments: HashMap::new(),
+        }
+    }
+
+    pub fn trim_text(&mut self, trim: bool) -> &mut Self {
+        self.reader.trim_text(trim);
+        self
+    }
+
+    pub fn expand_empty_elements(&mut self, expand: bool) -> &mut Self {
+        self.reader.expand_empty_elements(expand);
+        self
+    }
+
+    pub fn skip_unknown(&mut self, skip: bool) -> &mut Self {
+        self.skip_unknown = skip;
+        self
+    }
+
+    // Restricted values are only checked against their facets when asked, as servers often stray from them
+    pub fn validate(&mut self, validate: bool) -> &mut Self {
+        self.validate = validate;
+        self
+    }
+
+    pub fn validates(&self) -> bool {
+        self.validate
+    }
+
+    pub fn attachments(&mut self, attachments: HashMap<String, Vec<u8>>) -> &mut Self {
+        self.attachments = attachments;
+        self
+    }
+
+    pub fn decode<'b>(&self, bytes: &'b [u8]) -> quick_xml::Result<&'b str> {
+        self.reader.decode(bytes)
+    }
+
+    pub fn position(&self) -> usize {
+        self.reader.buffer_position()
+    }
+
+    fn error(&self, source: quick_xml::Error) -> XmlError {
+        XmlError::Xml {
+            source,
+            position: self.position(),
+        }
+    }
+
+    fn parse<T: FromStr>(&self, value: &str) -> Result<T, XmlError> {
+        value.parse().map_err(|_| XmlError::InvalidValue {
+            value: value.to_owned(),
+            position: self.position(),
+        })
+    }
+
+    pub fn resolve_namespace(&self, start: &events::BytesStart) -> Option<&[u8]> {
+        let name = start.name();
+        let prefix = match name.iter().position(|byte| *byte == b':') {
+            Some(index) => &name[..index],
+            None => &[][..],
+        };
+
+        self.scopes
+            .iter()
+            .rev()
+            .flatten()
+            .find(|(scope_prefix, _)| scope_prefix == prefix)
+            .map(|(_, namespace)| namespace.as_slice())
+    }
+
+    fn read_event(&mut self, buffer: &mut Vec<u8>) -> quick_xml::Result<events::Event<'static>> {
+        if let Some(event) = self.peeked.take() {
+            return Ok(event);
+        }
+
+        buffer.clear();
+        let event = self.reader.read_event(buffer).map(events::Event::into_owned)?;
+
+        match &event {
+            events::Event::Start(start) => self.scopes.push(
+                start
+                    .attributes()
+                    .flatten()
+                    .filter_map(|attribute| {
+                        let prefix = if attribute.key == b"xmlns" {
+                            &[][..]
+                        } else {
+                            attribute.key.strip_prefix(b"xmlns:")?
+                        };
+
+                        Some((prefix.to_vec(), attribute.value.into_owned()))
+                    })
+                    .collect(),
+            ),
+            events::Event::End(_) => {
+                self.scopes.pop();
+            }
+            _ => (),
+        }
+
+        Ok(event)
+    }
 }
 
 fn next_event<R: BufRead>(
     reader: &mut Reader<R>,
     buffer: &mut Vec<u8>,
-) -> Option<events::Event<'static>> {
+) -> Result<Option<events::Event<'static>>, XmlError> {
     loop {
-        match reader.read_event(buffer).unwrap() {
+        match reader.read_event(buffer).map_err(|error| reader.error(error))? {
             event
             @
             (events::Event::Start(_)
             | events::Event::Empty(_)
             | events::Event::End(_)
-            | events::Event::Text(_)) => break Some(event.into_owned()),
-            events::Event::Eof => return None,
+            | events::Event::Text(_)
+            | events::Event::CData(_)) => break Ok(Some(event)),
+            events::Event::Eof => return Ok(None),
             _ => (),
         }
     }
 }
 
+fn peek_event<'a, R: BufRead>(
+    reader: &'a mut Reader<R>,
+    buffer: &mut Vec<u8>,
+) -> Result<Option<&'a events::Event<'static>>, XmlError> {
+    if reader.peeked.is_none() {
+        reader.peeked = next_event(reader, buffer)?;
+    }
+
+    Ok(reader.peeked.as_ref())
+}
+
 pub fn is_start<'a>(event: events::Event<'a>, name: &str) -> Option<events::BytesStart<'a>> {
     if let events::Event::Start(start) = event {
         if start.local_name() == name.as_bytes() {
@@ -42,39 +199,333 @@ pub fn is_start<'a>(event: events::Event<'a>, name: &str) -> Option<events::Byte
     None
 }
 
-pub fn expect_start<'a, R: BufRead>(
+pub fn is_next_start<R: BufRead>(
     reader: &mut Reader<R>,
-    buffer: &'a mut Vec<u8>,
+    buffer: &mut Vec<u8>,
     name: &str,
-) -> Option<events::BytesStart<'a>> {
-    is_start(next_event(reader, buffer).unwrap(), name)
+) -> Result<bool, XmlError> {
+    is_next_start_ns(reader, buffer, "", name)
 }
 
-pub fn expect_value<'a, R: BufRead, T: FromStr>(
-    reader: &'a mut Reader<R>,
-    buffer: &'a mut Vec<u8>,
-) -> Option<T>
-where
-    <T as FromStr>::Err: Debug,
-{
-    if let Ok(events::Event::Text(text)) = reader.read_event(buffer) {
-        let unescaped = text.unescaped().unwrap();
-        let text = reader.decode(unescaped.as_ref()).unwrap();
-        return Some(text.parse().unwrap());
+fn matches_start<R: BufRead>(
+    reader: &Reader<R>,
+    start: &events::BytesStart,
+    namespace: &str,
+    name: &str,
+) -> bool {
+    // Elements match on their local name whatever prefix the server chose. Unqualified
+    // elements (no prefix and no default namespace, or xmlns="") carry no namespace, so
+    // only a resolved namespace conflicting with a non-empty expected one is rejected
+    start.local_name() == name.as_bytes()
+        && reader
+            .resolve_namespace(start)
+            .filter(|resolved| !resolved.is_empty())
+            .is_none_or(|resolved| namespace.is_empty() || resolved == namespace.as_bytes())
+}
+
+pub fn is_next_start_ns<R: BufRead>(
+    reader: &mut Reader<R>,
+    buffer: &mut Vec<u8>,
+    namespace: &str,
+    name: &str,
+) -> Result<bool, XmlError> {
+    peek_event(reader, buffer)?;
+
+    Ok(match &reader.peeked {
+        Some(events::Event::Start(start)) => matches_start(reader, start, namespace, name),
+        _ => false,
+    })
+}
+
+pub fn is_next_any_start<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<bool, XmlError> {
+    Ok(matches!(peek_event(reader, buffer)?, Some(events::Event::Start(_))))
+}
+
+pub fn is_next_nil<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<bool, XmlError> {
+    if let Some(events::Event::Start(start)) = peek_event(reader, buffer)? {
+        return Ok(start.attributes().flatten().any(|attribute| {
+            attribute.key.rsplit(|byte| *byte == b':').next() == Some(b"nil".as_ref())
+                && matches!(attribute.value.as_ref(), b"true" | b"1")
+        }));
     }
 
-    None
+    Ok(false)
 }
 
-pub fn expect_end<'a, R: BufRead>(
-    reader: &'a mut Reader<R>,
-    buffer: &'a mut Vec<u8>,
-) -> Option<events::BytesEnd<'a>> {
-    if let Ok(events::Event::End(end)) = reader.read_event(buffer) {
-        return Some(end);
+pub fn expect_start<R: BufRead>(
+    reader: &mut Reader<R>,
+    buffer: &mut Vec<u8>,
+    name: &str,
+) -> Result<events::BytesStart<'static>, XmlError> {
+    expect_start_ns(reader, buffer, "", name)
+}
+
+pub fn expect_start_ns<R: BufRead>(
+    reader: &mut Reader<R>,
+    buffer: &mut Vec<u8>,
+    namespace: &str,
+    name: &str,
+) -> Result<events::BytesStart<'static>, XmlError> {
+    loop {
+        match next_event(reader, buffer)? {
+            Some(events::Event::Start(start)) if matches_start(reader, &start, namespace, name) => {
+                return Ok(start)
+            }
+            Some(events::Event::Start(_)) if reader.skip_unknown => skip_subtree(reader, buffer)?,
+            Some(_) => {
+                return Err(XmlError::ExpectedStart {
+                    name: name.to_owned(),
+                    position: reader.position(),
+                })
+            }
+            None => return Err(XmlError::UnexpectedEof),
+        }
     }
+}
 
-    None
+pub fn expect_any_start<R: BufRead>(
+    reader: &mut Reader<R>,
+    buffer: &mut Vec<u8>,
+) -> Result<events::BytesStart<'static>, XmlError> {
+    match next_event(reader, buffer)? {
+        Some(events::Event::Start(start)) => Ok(start),
+        Some(_) => Err(XmlError::ExpectedStart {
+            name: "*".to_owned(),
+            position: reader.position(),
+        }),
+        None => Err(XmlError::UnexpectedEof),
+    }
+}
+
+fn skip_subtree<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<(), XmlError> {
+    let mut depth = 0usize;
+
+    while let Some(event) = next_event(reader, buffer)? {
+        match event {
+            events::Event::Start(_) => depth += 1,
+            events::Event::End(_) if depth == 0 => break,
+            events::Event::End(_) => depth -= 1,
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
+
+pub fn skip_element<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<(), XmlError> {
+    if let Some(events::Event::Start(_)) = next_event(reader, buffer)? {
+        skip_subtree(reader, buffer)?;
+    }
+
+    Ok(())
+}
+
+pub fn get_attribute<R: BufRead, T: FromStr>(
+    reader: &Reader<R>,
+    start: &events::BytesStart,
+    name: &str,
+) -> Result<Option<T>, XmlError> {
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(|error| reader.error(error))?;
+        let local_name = attribute.key.rsplit(|byte| *byte == b':').next().unwrap();
+
+        if local_name == name.as_bytes() {
+            let value = attribute.unescaped_value().map_err(|error| reader.error(error))?;
+            let value = reader.decode(value.as_ref()).map_err(|error| reader.error(error))?;
+            return reader.parse(value).map(Some);
+        }
+    }
+
+    Ok(None)
+}
+
+pub fn expect_attribute<R: BufRead, T: FromStr>(
+    reader: &Reader<R>,
+    start: &events::BytesStart,
+    name: &str,
+) -> Result<T, XmlError> {
+    get_attribute(reader, start, name)?.ok_or_else(|| XmlError::MissingAttribute {
+        name: name.to_owned(),
+        position: reader.position(),
+    })
+}
+
+pub fn expect_value<R: BufRead, T: FromStr>(
+    reader: &mut Reader<R>,
+    buffer: &mut Vec<u8>,
+) -> Result<T, XmlError> {
+    // An empty element has no text event, so the end is left for expect_end
+    if let Some(events::Event::End(_)) = peek_event(reader, buffer)? {
+        return "".parse().map_err(|_| XmlError::EmptyValue {
+            position: reader.position(),
+        });
+    }
+
+    match next_event(reader, buffer)? {
+        // quick-xml escapes CData content as it is read, so both are unescaped the same way
+        Some(events::Event::Text(text) | events::Event::CData(text)) => {
+            let unescaped = text.unescaped().map_err(|error| reader.error(error))?;
+            let text = reader.decode(unescaped.as_ref()).map_err(|error| reader.error(error))?;
+            reader.parse(text)
+        }
+        Some(_) => Err(XmlError::ExpectedText {
+            position: reader.position(),
+        }),
+        None => Err(XmlError::UnexpectedEof),
+    }
+}
+
+// The timezone is optional in xsd:dateTime, values without one are taken to be UTC
+#[cfg(feature = "chrono")]
+pub fn expect_date_time<R: BufRead>(
+    reader: &mut Reader<R>,
+    buffer: &mut Vec<u8>,
+) -> Result<chrono::DateTime<chrono::FixedOffset>, XmlError> {
+    use chrono::TimeZone;
+
+    let value: String = expect_value(reader, buffer)?;
+
+    value
+        .parse()
+        .or_else(|_| {
+            value.parse::<chrono::NaiveDateTime>().map(|naive| {
+                chrono::FixedOffset::east_opt(0).unwrap().from_utc_datetime(&naive)
+            })
+        })
+        .map_err(|_| XmlError::InvalidValue {
+            value,
+            position: reader.position(),
+        })
+}
+
+pub fn expect_optional_value<R: BufRead, T: FromStr>(
+    reader: &mut Reader<R>,
+    buffer: &mut Vec<u8>,
+) -> Result<Option<T>, XmlError> {
+    if let Some(events::Event::End(_)) = peek_event(reader, buffer)? {
+        return Ok(None);
+    }
+
+    expect_value(reader, buffer).map(Some)
+}
+
+pub fn expect_end<R: BufRead>(
+    reader: &mut Reader<R>,
+    buffer: &mut Vec<u8>,
+) -> Result<events::BytesEnd<'static>, XmlError> {
+    loop {
+        match next_event(reader, buffer)? {
+            Some(events::Event::End(end)) => return Ok(end),
+            Some(events::Event::Start(_)) if reader.skip_unknown => skip_subtree(reader, buffer)?,
+            Some(_) => {
+                return Err(XmlError::ExpectedEnd {
+                    position: reader.position(),
+                })
+            }
+            None => return Err(XmlError::UnexpectedEof),
+        }
+    }
+}
+
+pub fn read_text<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<String, XmlError> {
+    let mut depth = 0usize;
+    let mut result = String::new();
+
+    while let Some(event) = next_event(reader, buffer)? {
+        match event {
+            events::Event::Start(_) => depth += 1,
+            events::Event::End(_) if depth == 0 => break,
+            events::Event::End(_) => depth -= 1,
+            events::Event::Text(text) => {
+                let unescaped = text.unescaped().map_err(|error| reader.error(error))?;
+                result.push_str(reader.decode(unescaped.as_ref()).map_err(|error| reader.error(error))?);
+            }
+            _ => (),
+        }
+    }
+
+    Ok(result)
+}
+
+pub fn read_xml<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<String, XmlError> {
+    let mut depth = 0usize;
+    let mut writer = Writer::new(Vec::new());
+
+    // Stops before the end of the enclosing element so the caller can still expect it
+    loop {
+        match peek_event(reader, buffer)? {
+            Some(events::Event::End(_)) if depth == 0 => break,
+            Some(events::Event::Start(_)) => depth += 1,
+            Some(events::Event::End(_)) => depth -= 1,
+            Some(_) => (),
+            None => return Err(XmlError::UnexpectedEof),
+        }
+
+        if let Some(event) = reader.peeked.take() {
+            writer.write_event(event).map_err(|error| reader.error(error))?;
+        }
+    }
+
+    Ok(String::from_utf8_lossy(&writer.into_inner()).into_owned())
+}
+
+// Patterns in a restriction are alternatives and always match the whole value. XSD regular
+// expressions aren't quite the same syntax, so one that can't be compiled isn't enforced
+pub fn matches_pattern(value: &str, patterns: &[&str]) -> bool {
+    let pattern = patterns
+        .iter()
+        .map(|pattern| format!("(?:{})", pattern))
+        .collect::<Vec<_>>()
+        .join("|");
+
+    regex::Regex::new(&format!("^(?:{})$", pattern)).map_or(true, |regex| regex.is_match(value))
+}
+
+pub fn to_base64(bytes: &[u8]) -> String {
+    base64::encode(bytes)
+}
+
+pub fn write_base64<W: Write>(writer: &mut Writer<W>, bytes: &[u8]) {
+    // Inside mtom::collect the content is sent as an attachment and referenced from here
+    if let Some(id) = super::mtom::attach(bytes) {
+        let href = format!("cid:{}", id);
+        let include = events::BytesStart::borrowed_name(b"xop:Include")
+            .with_attributes([("xmlns:xop", "http://www.w3.org/2004/08/xop/include"), ("href", href.as_str())]);
+
+        writer.write_event(events::Event::Empty(include)).unwrap();
+    } else {
+        let text = to_base64(bytes);
+        writer.write_event(events::Event::Text(events::BytesText::from_plain_str(&text))).unwrap();
+    }
+}
+
+pub fn read_base64<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<Vec<u8>, XmlError> {
+    // MTOM responses replace the content with a reference to an attachment
+    if is_next_start(reader, buffer, "Include")? {
+        let include = expect_start(reader, buffer, "Include")?;
+        let href: String = expect_attribute(reader, &include, "href")?;
+        expect_end(reader, buffer)?;
+        expect_end(reader, buffer)?;
+
+        let id = href.trim_start_matches("cid:");
+
+        return reader.attachments.get(id).cloned().ok_or_else(|| XmlError::MissingAttachment {
+            id: id.to_owned(),
+            position: reader.position(),
+        });
+    }
+
+    let text = read_text(reader, buffer)?;
+    let text = text.split_whitespace().collect::<String>();
+
+    base64::decode(&text).map_err(|_| XmlError::InvalidValue {
+        value: text,
+        position: reader.position(),
+    })
+}
+
+impl ToXml for () {
+    fn to_xml<W: Write>(&self, _: &mut Writer<W>, _: bool) {}
 }
 
 impl ToXml for String {
@@ -83,8 +534,156 @@ impl ToXml for String {
     }
 }
 
+impl ToXml for AnyXml {
+    fn to_xml<W: Write>(&self, writer: &mut Writer<W>, _: bool) {
+        writer.inner().write_all(self.0.as_bytes()).unwrap();
+    }
+}
+
+impl FromXml for AnyXml {
+    fn from_xml<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, XmlError> {
+        read_xml(reader, buffer).map(Self)
+    }
+}
+
 impl FromXml for String {
-    fn from_xml<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Self {
-        expect_value(reader, buffer).unwrap()
+    fn from_xml<R: BufRead>(reader: &mut Reader<R>, buffer: &mut Vec<u8>) -> Result<Self, XmlError> {
+        expect_value(reader, buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reader(xml: &str) -> Reader<&[u8]> {
+        let mut reader = Reader::from_reader(xml.as_bytes());
+        reader.trim_text(true);
+        reader.expand_empty_elements(true);
+        reader
+    }
+
+    #[test]
+    fn same_local_name_in_two_namespaces() {
+        let mut reader = reader(r#"<root xmlns:a="urn:a" xmlns:b="urn:b"><b:name>second</b:name><a:name>first</a:name></root>"#);
+        let mut buffer = Vec::new();
+
+        expect_start(&mut reader, &mut buffer, "root").unwrap();
+        assert!(!is_next_start_ns(&mut reader, &mut buffer, "urn:a", "name").unwrap());
+
+        expect_start_ns(&mut reader, &mut buffer, "urn:b", "name").unwrap();
+        assert_eq!(expect_value::<_, String>(&mut reader, &mut buffer).unwrap(), "second");
+        expect_end(&mut reader, &mut buffer).unwrap();
+
+        expect_start_ns(&mut reader, &mut buffer, "urn:a", "name").unwrap();
+        assert_eq!(expect_value::<_, String>(&mut reader, &mut buffer).unwrap(), "first");
+    }
+
+    #[test]
+    fn truncated_and_mistyped_values_are_errors() {
+        let mut truncated = reader("<values><value>12");
+        let mut buffer = Vec::new();
+
+        expect_start(&mut truncated, &mut buffer, "values").unwrap();
+        expect_start(&mut truncated, &mut buffer, "value").unwrap();
+        assert_eq!(expect_value::<_, i32>(&mut truncated, &mut buffer).unwrap(), 12);
+        assert!(expect_end(&mut truncated, &mut buffer).is_err());
+
+        let mut mistyped = reader("<value>twelve</value>");
+
+        expect_start(&mut mistyped, &mut buffer, "value").unwrap();
+        assert!(matches!(
+            expect_value::<_, i32>(&mut mistyped, &mut buffer),
+            Err(XmlError::InvalidValue { value, .. }) if value == "twelve"
+        ));
+    }
+
+    #[test]
+    fn empty_values() {
+        let mut reader = reader("<values><name></name><count/></values>");
+        let mut buffer = Vec::new();
+
+        expect_start(&mut reader, &mut buffer, "values").unwrap();
+        expect_start(&mut reader, &mut buffer, "name").unwrap();
+        assert_eq!(expect_value::<_, String>(&mut reader, &mut buffer).unwrap(), "");
+        expect_end(&mut reader, &mut buffer).unwrap();
+
+        expect_start(&mut reader, &mut buffer, "count").unwrap();
+        assert!(matches!(
+            expect_value::<_, i32>(&mut reader, &mut buffer),
+            Err(XmlError::EmptyValue { .. })
+        ));
+        expect_end(&mut reader, &mut buffer).unwrap();
+    }
+
+    #[test]
+    fn cdata_values_are_kept_verbatim() {
+        let mut reader = reader("<value><![CDATA[<b>bold</b> & more]]></value>");
+        let mut buffer = Vec::new();
+
+        expect_start(&mut reader, &mut buffer, "value").unwrap();
+        assert_eq!(expect_value::<_, String>(&mut reader, &mut buffer).unwrap(), "<b>bold</b> & more");
+        expect_end(&mut reader, &mut buffer).unwrap();
+    }
+
+    #[test]
+    fn elements_match_by_local_name_and_namespace() {
+        for xml in [
+            r#"<ns1:value xmlns:ns1="urn:a">matched</ns1:value>"#,
+            r#"<other:value xmlns:other="urn:a">matched</other:value>"#,
+            r#"<value xmlns="urn:a">matched</value>"#,
+            r#"<value>matched</value>"#,
+            r#"<value xmlns="">matched</value>"#,
+        ] {
+            let mut reader = reader(xml);
+            let mut buffer = Vec::new();
+
+            expect_start_ns(&mut reader, &mut buffer, "urn:a", "value").unwrap();
+            assert_eq!(expect_value::<_, String>(&mut reader, &mut buffer).unwrap(), "matched", "{}", xml);
+        }
+
+        for xml in [r#"<value xmlns="urn:b">other</value>"#, r#"<b:value xmlns:b="urn:b">other</b:value>"#] {
+            let mut reader = reader(xml);
+            assert!(!is_next_start_ns(&mut reader, &mut Vec::new(), "urn:a", "value").unwrap(), "{}", xml);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn date_time_with_timezone() {
+        let mut reader = reader("<value>2021-10-01T12:00:00.5+01:00</value>");
+        let mut buffer = Vec::new();
+
+        expect_start(&mut reader, &mut buffer, "value").unwrap();
+        let value = expect_date_time(&mut reader, &mut buffer).unwrap();
+
+        assert_eq!(value.to_rfc3339(), "2021-10-01T12:00:00.500+01:00");
+        assert_eq!(value.offset().local_minus_utc(), 3600);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn date_time_without_timezone_is_utc() {
+        let mut reader = reader("<value>2021-10-01T12:00:00</value>");
+        let mut buffer = Vec::new();
+
+        expect_start(&mut reader, &mut buffer, "value").unwrap();
+        let value = expect_date_time(&mut reader, &mut buffer).unwrap();
+
+        assert_eq!(value.to_rfc3339(), "2021-10-01T12:00:00+00:00");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn invalid_date_time() {
+        let mut reader = reader("<value>yesterday</value>");
+        let mut buffer = Vec::new();
+
+        expect_start(&mut reader, &mut buffer, "value").unwrap();
+
+        assert!(matches!(
+            expect_date_time(&mut reader, &mut buffer),
+            Err(XmlError::InvalidValue { value, .. }) if value == "yesterday"
+        ));
     }
 }
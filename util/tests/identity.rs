@@ -0,0 +1,17 @@
+#![cfg(feature = "tls-client-auth")]
+
+use suds_util::soap::{Client, Identity};
+
+fn identity() -> Identity {
+    Identity::from_pkcs8_pem(include_bytes!("client.crt"), include_bytes!("client.key")).unwrap()
+}
+
+#[test]
+fn clients_accept_an_identity() {
+    assert!(Client::with_identity("https://example.com/service", identity()).is_ok());
+    assert!(Client::builder("https://example.com/service")
+        .identity(identity())
+        .timeout(std::time::Duration::from_secs(5))
+        .build()
+        .is_ok());
+}
@@ -0,0 +1,78 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    thread,
+};
+
+use native_tls::{Identity, TlsAcceptor};
+use suds_util::soap::{Client, Envelope};
+
+// Answers a single request over TLS with a certificate no client trusts
+fn self_signed_server() -> &'static str {
+    let identity = Identity::from_pkcs8(include_bytes!("client.crt"), include_bytes!("client.key")).unwrap();
+    let acceptor = TlsAcceptor::new(identity).unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("https://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+
+        // A client rejecting the certificate fails the handshake, which ends the test server
+        let stream = match acceptor.accept(stream) {
+            Ok(stream) => stream,
+            Err(_) => return,
+        };
+
+        let mut reader = BufReader::new(stream);
+        let mut length = 0;
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+
+            match line.trim_end().split_once(':') {
+                Some((key, value)) if key.eq_ignore_ascii_case("content-length") => length = value.trim().parse().unwrap(),
+                Some(_) => (),
+                None => break,
+            }
+        }
+
+        let mut request = vec![0; length];
+        reader.read_exact(&mut request).unwrap();
+
+        let response = "<Envelope><Body>pong</Body></Envelope>";
+        write!(
+            reader.get_mut(),
+            "HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            response.len(),
+            response
+        )
+        .unwrap();
+    });
+
+    Box::leak(url.into_boxed_str())
+}
+
+fn ping(client: Client) -> bool {
+    client
+        .send::<_, _, String>("urn:ping", Envelope::new(String::new()))
+        .is_ok()
+}
+
+#[test]
+fn self_signed_certificates_are_rejected_by_default() {
+    assert!(!ping(Client::new(self_signed_server())));
+    assert!(!ping(Client::danger_accept_invalid_certs(self_signed_server(), false).unwrap()));
+}
+
+#[test]
+fn self_signed_certificates_can_be_accepted() {
+    let client = Client::danger_accept_invalid_certs(self_signed_server(), true).unwrap();
+    let response: Envelope<String> = client.send("urn:ping", Envelope::new(String::new())).unwrap();
+
+    assert_eq!(response.into_body(), "pong");
+}
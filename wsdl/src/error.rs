@@ -19,4 +19,13 @@ pub enum Error {
 
     #[error("Error parsing XML input")]
     XmlParseError(#[from] quick_xml::Error),
+
+    #[error("Unsupported element {0} inside {1}")]
+    UnsupportedElement(String, String),
+
+    #[error("Unknown namespace prefix {0}")]
+    UnknownNamespacePrefix(String),
+
+    #[error("Conflicting definitions of type {0}")]
+    ConflictingType(String),
 }
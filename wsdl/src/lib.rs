@@ -1,26 +1,92 @@
-use std::path::Path;
-use url::Url;
+// The parser also runs inside the macro at build time, so diagnostics only ever go through log
+#![deny(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)]
+
+use std::{io::BufRead, path::Path};
+
+pub use url::Url;
 
 mod parser;
 
 pub mod error;
+pub mod resolver;
 pub mod types;
+pub mod visitor;
+
+pub fn to_url(url: &str) -> Result<Url, error::Error> {
+    match Url::parse(url) {
+        Ok(url) => Ok(url),
+        Err(url::ParseError::RelativeUrlWithoutBase) => {
+            let path = Path::new(url)
+                .canonicalize()
+                .map_err(|err| error::Error::PathConversionError(Some(err)))?;
+
+            if path.is_dir() {
+                Ok(Url::from_directory_path(&path).unwrap())
+            } else {
+                Ok(Url::from_file_path(&path).unwrap())
+            }
+        }
+        Err(err) => Err(err.into()),
+    }
+}
 
 pub fn parse<S: AsRef<str>>(
     url: S,
 ) -> Result<(types::Definition, types::Namespaces), error::Error> {
-    let url = {
-        match Url::parse(url.as_ref()) {
-            Ok(url) => url,
-            Err(url::ParseError::RelativeUrlWithoutBase) => Url::from_file_path(
-                &Path::new(url.as_ref())
-                    .canonicalize()
-                    .map_err(|err| error::Error::PathConversionError(Some(err)))?,
-            )
-            .unwrap(),
-            Err(err) => return Err(err.into()),
-        }
-    };
+    parse_with_resolver(url, &resolver::DefaultResolver)
+}
+
+pub fn parse_with_resolver<S: AsRef<str>>(
+    url: S,
+    resolver: &dyn resolver::Resolver,
+) -> Result<(types::Definition, types::Namespaces), error::Error> {
+    parser::parse(to_url(url.as_ref())?, resolver, &mut visitor::DefaultVisitor)
+}
+
+pub fn parse_with<S: AsRef<str>>(
+    url: S,
+    visitor: &mut dyn visitor::Visitor,
+) -> Result<(types::Definition, types::Namespaces), error::Error> {
+    parser::parse(to_url(url.as_ref())?, &resolver::DefaultResolver, visitor)
+}
 
-    parser::parse(url)
+// Unsupported elements are returned as warnings, or rejected in strict mode
+pub fn parse_with_options<S: AsRef<str>>(
+    url: S,
+    options: types::ParseOptions,
+) -> Result<(types::Definition, types::Namespaces, Vec<types::Unsupported>), error::Error> {
+    parser::parse_with_options(
+        to_url(url.as_ref())?,
+        &resolver::DefaultResolver,
+        &mut visitor::DefaultVisitor,
+        options,
+    )
+}
+
+// Only imports are fetched, relative to the base URL and through the resolver
+pub fn parse_reader<R: BufRead>(
+    reader: R,
+    base: Url,
+) -> Result<(types::Definition, types::Namespaces), error::Error> {
+    parse_reader_with_resolver(reader, base, &resolver::DefaultResolver)
+}
+
+pub fn parse_reader_with_resolver<R: BufRead>(
+    reader: R,
+    base: Url,
+    resolver: &dyn resolver::Resolver,
+) -> Result<(types::Definition, types::Namespaces), error::Error> {
+    parser::parse_reader(reader, base, resolver, &mut visitor::DefaultVisitor)
+}
+
+pub fn parse_str(
+    contents: &str,
+    base: Url,
+) -> Result<(types::Definition, types::Namespaces), error::Error> {
+    parser::parse_str(
+        contents,
+        base,
+        &resolver::DefaultResolver,
+        &mut visitor::DefaultVisitor,
+    )
 }
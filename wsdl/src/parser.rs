@@ -1,21 +1,30 @@
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use log::{debug, trace};
 use quick_xml::{
     events::{attributes::Attributes, BytesStart, BytesText, Event},
     Reader,
 };
 use std::{
-    collections::HashMap,
-    io::{BufRead, BufReader},
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    io::BufRead,
 };
 use url::Url;
 
 use crate::types::FieldKind;
 
+const XSD_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema";
+const SOAP12_NAMESPACE: &str = "http://schemas.xmlsoap.org/wsdl/soap12/";
+
 use super::{
     error,
+    resolver::Resolver,
     types::{
-        Binding, BindingOperation, Definition, Field, Message, NamespacedName, Namespaces,
-        Operation, Port, PortType, Service, Type, TypeKind,
+        Binding, BindingHeader, BindingOperation, Definition, Facets, Fault, Field, Message,
+        NamespacedName, Namespaces, Operation, ParseOptions, Port, PortType, Service, SoapVersion,
+        SubstitutionGroup, Type, TypeKind, Unsupported,
     },
+    visitor::Visitor,
 };
 
 fn get_attributes<B: BufRead, const N: usize>(
@@ -41,6 +50,54 @@ fn get_attributes<B: BufRead, const N: usize>(
     Ok(result)
 }
 
+fn get_local_attribute<B: BufRead>(
+    reader: &Reader<B>,
+    attributes: Attributes<'_>,
+    name: &str,
+) -> Result<Option<String>, error::Error> {
+    for attribute in attributes {
+        let attribute = attribute?;
+        let (_, local_name) = split_namespaced_name(reader.decode(attribute.key)?);
+
+        if local_name == name {
+            return Ok(Some(reader.decode(attribute.value.as_ref())?.to_owned()));
+        }
+    }
+
+    Ok(None)
+}
+
+fn declared_encoding(bytes: &[u8]) -> Option<&'static Encoding> {
+    let declaration = bytes.strip_prefix(b"<?xml")?;
+    let end = declaration.windows(2).position(|window| window == b"?>")?;
+    let declaration = std::str::from_utf8(&declaration[..end]).ok()?;
+
+    let (_, value) = declaration.split_once("encoding")?;
+    let value = value.trim_start().strip_prefix('=')?.trim_start();
+    let label = value
+        .strip_prefix('"')
+        .or_else(|| value.strip_prefix('\''))?
+        .split(['"', '\''])
+        .next()?;
+
+    // A declaration readable as ASCII can't really be UTF-16, which maps to UTF-8 here
+    Encoding::for_label(label.as_bytes()).map(Encoding::output_encoding)
+}
+
+// Documents are transcoded to UTF-8 up front using their byte order mark or declared encoding
+fn decode(bytes: &[u8]) -> Cow<'_, str> {
+    let encoding = match Encoding::for_bom(bytes) {
+        Some((encoding, _)) => encoding,
+        None => match bytes {
+            [0x3C, 0x00, 0x3F, 0x00, ..] => UTF_16LE,
+            [0x00, 0x3C, 0x00, 0x3F, ..] => UTF_16BE,
+            _ => declared_encoding(bytes).unwrap_or(UTF_8),
+        },
+    };
+
+    encoding.decode_with_bom_removal(bytes).0
+}
+
 fn split_namespaced_name(prefixed_name: &str) -> (Option<&str>, &str) {
     let mut split = prefixed_name.split(':');
     let first = split.next().unwrap();
@@ -53,18 +110,37 @@ fn split_namespaced_name(prefixed_name: &str) -> (Option<&str>, &str) {
     }
 }
 
+fn merge_attributes(kind: Option<TypeKind>, attributes: Vec<Field>) -> Option<TypeKind> {
+    match kind {
+        Some(TypeKind::Struct(mut fields)) => {
+            fields.extend(attributes);
+            Some(TypeKind::Struct(fields))
+        }
+
+        None if !attributes.is_empty() => Some(TypeKind::Struct(attributes)),
+
+        kind => kind,
+    }
+}
+
 #[derive(Clone, Default)]
 struct CurrentNamespaces {
     target: Vec<String>,
-    namespaces: HashMap<Option<String>, String>,
+    scopes: Vec<HashMap<Option<String>, String>>,
 }
 
-struct Parser {
+struct Parser<'a> {
     root: Url,
+    resolver: &'a dyn Resolver,
+    visitor: &'a mut dyn Visitor,
 
     definition: Definition,
     namespaces: Namespaces,
     current_namespaces: CurrentNamespaces,
+    include_namespace: Option<String>,
+    visited: HashSet<Url>,
+    strict: bool,
+    unsupported: Vec<Unsupported>,
 }
 
 #[derive(Debug)]
@@ -76,10 +152,14 @@ enum ParseState {
     Element {
         name: String,
         kind: Option<TypeKind>,
+        substitution_group: Option<NamespacedName>,
+        documentation: Option<String>,
     },
     ComplexType {
         name: Option<String>,
         kind: Option<TypeKind>,
+        attributes: Vec<Field>,
+        documentation: Option<String>,
     },
     ComplexContent {
         fields: Vec<Field>
@@ -88,23 +168,53 @@ enum ParseState {
         fields: Vec<Field>
     },
     SimpleContent {
-        ty: Option<NamespacedName>
+        ty: Option<NamespacedName>,
+        attributes: Vec<Field>,
     },
     SimpleExtension {
-        ty: NamespacedName
+        ty: NamespacedName,
+        attributes: Vec<Field>,
     },
     Sequence(Vec<Field>),
+    Choice(Vec<Field>),
     SequenceElement {
         name: String,
+        reference: Option<NamespacedName>,
         ty: Option<NamespacedName>,
         inner: Option<TypeKind>,
+        min_occurs: Option<String>,
+        max_occurs: Option<String>,
+        nillable: Option<String>,
+        documentation: Option<String>,
     },
     SimpleType {
-        name: String,
+        name: Option<String>,
         ty: Option<NamespacedName>,
+        item: Option<NamespacedName>,
+        values: Vec<String>,
+        facets: Facets,
+        documentation: Option<String>,
     },
     Restriction {
         ty: NamespacedName,
+        values: Vec<String>,
+        facets: Facets,
+    },
+    Enumeration {
+        value: String,
+    },
+    ListType {
+        item: NamespacedName,
+    },
+    Facet {
+        name: String,
+        value: String,
+    },
+    Attribute {
+        name: String,
+        ty: NamespacedName,
+        required: bool,
+        documentation: Option<String>,
     },
 
     Message {
@@ -112,7 +222,7 @@ enum ParseState {
         parts: Vec<Field>,
     },
     Part {
-        name: String,
+        name: NamespacedName,
         element: NamespacedName,
     },
 
@@ -125,23 +235,31 @@ enum ParseState {
         documentation: Option<String>,
         input: Option<NamespacedName>,
         output: Option<NamespacedName>,
+        faults: Vec<Fault>,
     },
     Documentation(Option<String>),
+    Annotation(Option<String>),
     Input {
         message: NamespacedName,
     },
     Output {
         message: NamespacedName,
     },
+    Fault {
+        name: String,
+        message: NamespacedName,
+    },
 
     Binding {
         name: String,
         ty: NamespacedName,
         transport: Option<String>,
+        version: SoapVersion,
         operations: Vec<BindingOperation>,
     },
     Transport {
         transport: String,
+        version: SoapVersion,
     },
     BindingOperation {
         name: String,
@@ -149,6 +267,9 @@ enum ParseState {
         style: Option<String>,
         input: Option<String>,
         output: Option<String>,
+        namespace: Option<String>,
+        input_headers: Vec<BindingHeader>,
+        output_headers: Vec<BindingHeader>,
     },
     OperationAction {
         action: String,
@@ -156,12 +277,23 @@ enum ParseState {
     },
     BindingInput {
         body: Option<String>,
+        namespace: Option<String>,
+        headers: Vec<BindingHeader>,
     },
     BindingOutput {
         body: Option<String>,
+        namespace: Option<String>,
+        headers: Vec<BindingHeader>,
     },
     BindingBody {
         body: String,
+        namespace: Option<String>,
+    },
+    Header {
+        header: BindingHeader,
+    },
+    HeaderFault {
+        fault: BindingHeader,
     },
 
     Service {
@@ -180,6 +312,7 @@ enum ParseState {
     Import {
         namespace: Option<String>,
     },
+    Include,
 
     Other(String),
 }
@@ -193,8 +326,22 @@ impl CurrentNamespaces {
         self.target.pop();
     }
 
+    pub fn target_namespace(&self) -> Option<&String> {
+        self.target.last()
+    }
+
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    pub fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
     pub fn add_namespace_prefix(&mut self, prefix: Option<String>, namespace: &str) {
-        self.namespaces.insert(prefix, namespace.to_owned());
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(prefix, namespace.to_owned());
+        }
     }
 
     pub fn target_namespaced(&self, namespaces: &mut Namespaces, name: String) -> NamespacedName {
@@ -210,23 +357,45 @@ impl CurrentNamespaces {
         namespaces: &mut Namespaces,
         prefix: Option<String>,
         name: String,
-    ) -> NamespacedName {
-        match self.namespaces.get(&prefix) {
-            Some(value) => NamespacedName::new(namespaces, value, name),
-            None => unimplemented!(),
+    ) -> Result<NamespacedName, error::Error> {
+        // The innermost declaration of a prefix shadows any made by its ancestors
+        match self.scopes.iter().rev().find_map(|scope| scope.get(&prefix)) {
+            Some(value) => Ok(NamespacedName::new(namespaces, value, name)),
+            None => Err(error::Error::UnknownNamespacePrefix(prefix.unwrap_or_default())),
         }
     }
 }
 
-impl Parser {
-    fn new(url: Url) -> Self {
+impl<'a> Parser<'a> {
+    fn new(url: Url, resolver: &'a dyn Resolver, visitor: &'a mut dyn Visitor) -> Self {
         Self {
             root: url.clone(),
+            resolver,
+            visitor,
 
             definition: Default::default(),
             namespaces: Default::default(),
             current_namespaces: Default::default(),
+            include_namespace: None,
+            visited: HashSet::new(),
+            strict: false,
+            unsupported: Vec::new(),
+        }
+    }
+
+    fn unsupported(&mut self, element: &str, context: &str) -> Result<(), error::Error> {
+        trace!("FOUND {} INSIDE {} BLOCK", element, context);
+
+        if self.strict {
+            return Err(error::Error::UnsupportedElement(element.to_owned(), context.to_owned()));
         }
+
+        self.unsupported.push(Unsupported {
+            element: element.to_owned(),
+            context: context.to_owned(),
+        });
+
+        Ok(())
     }
 
     fn push_target_namespace(&mut self, namespace: String) {
@@ -242,52 +411,186 @@ impl Parser {
             .add_namespace_prefix(prefix, namespace);
     }
 
+    fn add_type(&mut self, ty: Type) -> Result<(), error::Error> {
+        let is_self_alias =
+            |ty: &Type| matches!(&ty.kind, TypeKind::Alias(alias) if *alias == ty.name);
+
+        // Imported schemas can declare the same type again, which is only fine if they agree
+        if !is_self_alias(&ty) {
+            let existing = self
+                .definition
+                .types
+                .iter()
+                .find(|existing| existing.name == ty.name && !is_self_alias(existing));
+
+            match existing {
+                Some(existing) if structure(&existing.kind) == structure(&ty.kind) => return Ok(()),
+                Some(_) => return Err(error::Error::ConflictingType(ty.name.name)),
+                None => (),
+            }
+        }
+
+        self.visitor.visit_type(&ty);
+        self.definition.types.push(ty);
+        Ok(())
+    }
+
+    fn add_message(&mut self, message: Message) {
+        self.visitor.visit_message(&message);
+        self.definition.messages.push(message);
+    }
+
+    fn add_service(&mut self, service: Service) {
+        self.visitor.visit_service(&service);
+        self.definition.services.push(service);
+    }
+
     fn target_namespaced(&mut self, name: String) -> NamespacedName {
         self.current_namespaces
             .target_namespaced(&mut self.namespaces, name)
     }
 
-    fn resolved_prefix(&mut self, prefix: Option<String>, name: String) -> NamespacedName {
+    fn resolved_prefix(
+        &mut self,
+        prefix: Option<String>,
+        name: String,
+    ) -> Result<NamespacedName, error::Error> {
         self.current_namespaces
             .resolved_prefix(&mut self.namespaces, prefix, name)
     }
 
-    fn resolve_namespace(&mut self, prefixed_name: &str) -> NamespacedName {
+    fn resolve_namespace(&mut self, prefixed_name: &str) -> Result<NamespacedName, error::Error> {
         let (prefix, local_name) = split_namespaced_name(prefixed_name);
 
         match prefix {
-            Some("tns") => self.target_namespaced(local_name.to_owned()),
+            Some("tns") => Ok(self.target_namespaced(local_name.to_owned())),
 
             _ => self.resolved_prefix(prefix.map(ToOwned::to_owned), local_name.to_owned()),
         }
     }
 
+    fn attribute_state<B: BufRead>(
+        &mut self,
+        reader: &Reader<B>,
+        start: &BytesStart,
+        parent: &str,
+    ) -> Result<ParseState, error::Error> {
+        let [name, ty, attribute_use] =
+            get_attributes(reader, start.attributes(), ["name", "type", "use"])?;
+
+        // Attribute references would need the schema's global attributes, which aren't collected
+        let name = if let Some(name) = name {
+            name
+        } else {
+            return Err(error::Error::UnsupportedElement(
+                "attribute without a name".to_owned(),
+                parent.to_owned(),
+            ));
+        };
+
+        let ty = if let Some(ty) = ty {
+            self.resolve_namespace(&ty)?
+        } else {
+            NamespacedName::new(&mut self.namespaces, XSD_NAMESPACE, "string".to_owned())
+        };
+
+        Ok(ParseState::Attribute {
+            name,
+            ty,
+            required: attribute_use.as_deref() == Some("required"),
+            documentation: None,
+        })
+    }
+
+    fn binding_header<B: BufRead>(
+        &mut self,
+        reader: &Reader<B>,
+        start: &BytesStart,
+        parent: &str,
+    ) -> Result<BindingHeader, error::Error> {
+        let [message, part, body] =
+            get_attributes(reader, start.attributes(), ["message", "part", "use"])?;
+
+        let (message, part) = if let (Some(message), Some(part)) = (message, part) {
+            (self.resolve_namespace(&message)?, part)
+        } else {
+            return Err(error::Error::UnsupportedElement(
+                "header without a message and part".to_owned(),
+                parent.to_owned(),
+            ));
+        };
+
+        Ok(BindingHeader {
+            message,
+            part,
+            body,
+            faults: Vec::new(),
+        })
+    }
+
     fn parse(mut self) -> Result<(Definition, Namespaces), error::Error> {
         self.parse_url(self.root.clone())?;
         Ok((self.definition, self.namespaces))
     }
 
+    fn parse_str(mut self, contents: &str) -> Result<(Definition, Namespaces), error::Error> {
+        debug!("Parsing string with base URL: {}", self.root);
+
+        self.visited.insert(self.root.clone());
+        let contents = contents.trim_start_matches('\u{feff}');
+        self.parse_xml(self.root.clone(), Reader::from_str(contents))?;
+        Ok((self.definition, self.namespaces))
+    }
+
+    fn parse_reader<R: BufRead>(mut self, mut reader: R) -> Result<(Definition, Namespaces), error::Error> {
+        debug!("Parsing reader with base URL: {}", self.root);
+
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).map_err(quick_xml::Error::Io)?;
+        let contents = decode(&bytes);
+
+        self.visited.insert(self.root.clone());
+        self.parse_xml(self.root.clone(), Reader::from_str(&contents))?;
+        Ok((self.definition, self.namespaces))
+    }
+
     fn parse_url(&mut self, url: Url) -> Result<(), error::Error> {
-        println!("PARSING URL: {}", url);
-
-        let result = match url.scheme() {
-            "file" => self.parse_xml(
-                url.clone(),
-                Reader::from_file(
-                    url.to_file_path()
-                        .map_err(|()| error::Error::PathConversionError(None))?,
-                )
-                .map_err(error::Error::FileOpenError)?,
-            ),
+        let url = self.resolver.resolve(&url);
+
+        if !self.visited.insert(url.clone()) {
+            debug!("Skipping already parsed URL: {}", url);
+            return Ok(());
+        }
 
-            "http" | "https" => self.parse_xml(url.clone(), Reader::from_reader(BufReader::new(
-                reqwest::blocking::get(url)?,
-            ))),
+        debug!("Parsing URL: {}", url);
 
-            other => Err(error::Error::UnsupportedScheme(other.into())),
+        let bytes = match url.scheme() {
+            "file" => std::fs::read(
+                url.to_file_path()
+                    .map_err(|()| error::Error::PathConversionError(None))?,
+            )
+            .map_err(|err| error::Error::FileOpenError(quick_xml::Error::Io(err)))?,
+
+            "http" | "https" => reqwest::blocking::get(url.clone())?.bytes()?.to_vec(),
+
+            other => return Err(error::Error::UnsupportedScheme(other.into())),
         };
 
-        println!("FINISHED PARSING FILE");
+        let contents = decode(&bytes);
+
+        // Prefixes declared by the importing document aren't in scope inside the imported one
+        let scopes = std::mem::take(&mut self.current_namespaces.scopes);
+        let result = self.parse_xml(url, Reader::from_str(&contents));
+        self.current_namespaces.scopes = scopes;
+
+        debug!("Finished parsing file");
+        result
+    }
+
+    fn parse_schema_url(&mut self, url: Url, include_namespace: Option<String>) -> Result<(), error::Error> {
+        let previous = std::mem::replace(&mut self.include_namespace, include_namespace);
+        let result = self.parse_url(url);
+        self.include_namespace = previous;
         result
     }
 
@@ -301,8 +604,6 @@ impl Parser {
                 reader.read_namespaced_event(&mut buffer, &mut namespace_buffer)?;
 
             match event {
-                Event::Decl(..) => (),
-
                 Event::Start(start) => self.handle_start(&mut stack, &reader, start, namespace, &url)?,
                 Event::End(..) => self.handle_end(&mut stack)?,
 
@@ -311,26 +612,24 @@ impl Parser {
                     self.handle_end(&mut stack)?;
                 }
 
-                Event::Text(text) => self.handle_text(&mut stack, &reader, text)?,
+                Event::Text(text) | Event::CData(text) => self.handle_text(&mut stack, &reader, text)?,
 
-                event => {
-                    println!("{:?}", event);
+                // Comments and processing instructions can split text, which is joined back together
+                Event::Comment(..) | Event::PI(..) => (),
 
-                    if let Event::Eof = event {
-                        break;
-                    }
-                }
+                Event::Decl(..) | Event::DocType(..) => (),
+                Event::Eof => break,
             }
         }
 
         Ok(())
     }
 
-    fn handle_start<'a, B: BufRead>(
+    fn handle_start<B: BufRead>(
         &mut self,
         stack: &mut Vec<ParseState>,
         reader: &Reader<B>,
-        start: BytesStart<'a>,
+        start: BytesStart<'_>,
         namespace_bytes: Option<&[u8]>,
         url: &Url
     ) -> Result<(), error::Error> {
@@ -339,22 +638,42 @@ impl Parser {
         let state = stack.pop();
         let mut new_state = Some(ParseState::Other(local_name.to_owned()));
 
+        self.current_namespaces.push_scope();
+
         for attribute in start.attributes() {
             let attribute = attribute?;
             let key = reader.decode(attribute.key)?;
             let (prefix, value) = split_namespaced_name(key);
 
-            if prefix == Some("xmlns") {
-                self.add_namespace_prefix(
-                    Some(value.to_owned()),
+            match (prefix, value) {
+                (Some("xmlns"), prefix) => self.add_namespace_prefix(
+                    Some(prefix.to_owned()),
+                    reader.decode(attribute.value.as_ref())?,
+                ),
+
+                // A default namespace declaration qualifies unprefixed names
+                (None, "xmlns") => self.add_namespace_prefix(
+                    None,
                     reader.decode(attribute.value.as_ref())?,
-                );
+                ),
+
+                _ => (),
             }
         }
 
         match state {
+            // Documentation can appear in nearly any context, it is kept wherever the model has room for it
+            Some(ref state) if local_name == "annotation" && accepts_documentation(state) => {
+                new_state = Some(ParseState::Annotation(None))
+            }
+
+            Some(ref state) if local_name == "documentation" && accepts_documentation(state) => {
+                new_state = Some(ParseState::Documentation(None))
+            }
+
             None => match local_name {
-                "definitions" => {
+                // WSDL 2.0 renames the root element but otherwise maps onto the same model
+                "definitions" | "description" => {
                     let [namespace] =
                         get_attributes(reader, start.attributes(), ["targetNamespace"])?;
 
@@ -371,6 +690,9 @@ impl Parser {
                     let [namespace] =
                         get_attributes(reader, start.attributes(), ["targetNamespace"])?;
 
+                    // Included schemas without their own targetNamespace take on the includer's
+                    let namespace = namespace.or_else(|| self.include_namespace.clone());
+
                     if let Some(namespace) = namespace {
                         self.push_target_namespace(namespace);
                         self.add_namespace_prefix(
@@ -400,8 +722,8 @@ impl Parser {
                         unimplemented!()
                     };
 
-                    self.parse_url(self.root.join(&location)?)?;
-                    println!("BACK TO {}", url);
+                    self.parse_schema_url(url.join(&location)?, None)?;
+                    debug!("Back to {}", url);
 
                     new_state = Some(ParseState::Import { namespace });
                 }
@@ -423,7 +745,7 @@ impl Parser {
                     });
                 }
 
-                "portType" => {
+                "portType" | "interface" => {
                     let [name] = get_attributes(reader, start.attributes(), ["name"])?;
 
                     let name = if let Some(name) = name {
@@ -439,7 +761,8 @@ impl Parser {
                 }
 
                 "binding" => {
-                    let [name, ty] = get_attributes(reader, start.attributes(), ["name", "type"])?;
+                    let [name, ty, interface] =
+                        get_attributes(reader, start.attributes(), ["name", "type", "interface"])?;
 
                     let name = if let Some(name) = name {
                         name
@@ -447,8 +770,19 @@ impl Parser {
                         unimplemented!()
                     };
 
-                    let ty = if let Some(ty) = ty {
-                        self.resolve_namespace(&ty)
+                    // WSDL 2.0 bindings name their interface, and use type for the binding kind
+                    let (ty, transport, version) = if let Some(interface) = interface {
+                        let protocol = get_local_attribute(reader, start.attributes(), "protocol")?;
+                        let version = get_local_attribute(reader, start.attributes(), "version")?;
+
+                        let version = match version.as_deref() {
+                            Some("1.1") => SoapVersion::Soap11,
+                            _ => SoapVersion::Soap12,
+                        };
+
+                        (self.resolve_namespace(&interface)?, Some(protocol.unwrap_or_default()), version)
+                    } else if let Some(ty) = ty {
+                        (self.resolve_namespace(&ty)?, None, SoapVersion::default())
                     } else {
                         unimplemented!()
                     };
@@ -456,7 +790,8 @@ impl Parser {
                     new_state = Some(ParseState::Binding {
                         name,
                         ty,
-                        transport: None,
+                        transport,
+                        version,
                         operations: Vec::new(),
                     });
                 }
@@ -476,7 +811,7 @@ impl Parser {
                     });
                 }
 
-                _ => println!("FOUND {} INSIDE DEFINITION BLOCK", local_name),
+                _ => self.unsupported(local_name, "definition")?,
             },
 
             Some(ParseState::Types) => match local_name {
@@ -512,18 +847,22 @@ impl Parser {
                         unimplemented!()
                     };
 
-                    self.parse_url(self.root.join(&location)?)?;
-                    println!("BACK TO {}", url);
+                    self.parse_schema_url(url.join(&location)?, None)?;
+                    debug!("Back to {}", url);
 
                     new_state = Some(ParseState::Import { namespace });
                 }
 
-                _ => println!("FOUND {} INSIDE TYPES BLOCK", local_name),
+                _ => self.unsupported(local_name, "types")?,
             },
 
             Some(ParseState::Schema { .. }) => match local_name {
                 "element" => {
-                    let [name, ty] = get_attributes(reader, start.attributes(), ["name", "type"])?;
+                    let [name, ty, substitution_group] = get_attributes(
+                        reader,
+                        start.attributes(),
+                        ["name", "type", "substitutionGroup"],
+                    )?;
 
                     let name = if let Some(name) = name {
                         name
@@ -532,12 +871,21 @@ impl Parser {
                     };
 
                     let kind = if let Some(ty) = ty {
-                        Some(TypeKind::Alias(self.resolve_namespace(&ty)))
+                        Some(TypeKind::Alias(self.resolve_namespace(&ty)?))
                     } else {
                         None
                     };
 
-                    new_state = Some(ParseState::Element { name, kind })
+                    let substitution_group = substitution_group
+                        .map(|head| self.resolve_namespace(&head))
+                        .transpose()?;
+
+                    new_state = Some(ParseState::Element {
+                        name,
+                        kind,
+                        substitution_group,
+                        documentation: None,
+                    })
                 }
 
                 "complexType" => {
@@ -552,6 +900,8 @@ impl Parser {
                     new_state = Some(ParseState::ComplexType {
                         kind: None,
                         name: Some(name),
+                        attributes: Vec::new(),
+                        documentation: None,
                     });
                 }
 
@@ -564,10 +914,33 @@ impl Parser {
                         unimplemented!()
                     };
 
-                    new_state = Some(ParseState::SimpleType { name, ty: None })
+                    new_state = Some(ParseState::SimpleType {
+                        name: Some(name),
+                        ty: None,
+                        item: None,
+                        values: Vec::new(),
+                        facets: Facets::default(),
+                        documentation: None,
+                    })
+                }
+
+                "include" => {
+                    let [location] = get_attributes(reader, start.attributes(), ["schemaLocation"])?;
+
+                    let location = if let Some(location) = location {
+                        location
+                    } else {
+                        unimplemented!()
+                    };
+
+                    let namespace = self.current_namespaces.target_namespace().cloned();
+                    self.parse_schema_url(url.join(&location)?, namespace)?;
+                    debug!("Back to {}", url);
+
+                    new_state = Some(ParseState::Include);
                 }
 
-                "include" | "import" => {
+                "import" => {
                     let [location, namespace] = get_attributes(
                         reader,
                         start.attributes(),
@@ -580,13 +953,13 @@ impl Parser {
                         unimplemented!()
                     };
 
-                    self.parse_url(self.root.join(&location)?)?;
-                    println!("BACK TO {}", url);
+                    self.parse_schema_url(url.join(&location)?, None)?;
+                    debug!("Back to {}", url);
 
                     new_state = Some(ParseState::Import { namespace });
                 }
 
-                _ => println!("FOUND {} INSIDE SCHEMA BLOCK", local_name),
+                _ => self.unsupported(local_name, "schema")?,
             },
 
             Some(ParseState::Element { .. }) => match local_name {
@@ -594,20 +967,37 @@ impl Parser {
                     new_state = Some(ParseState::ComplexType {
                         kind: None,
                         name: None,
+                        attributes: Vec::new(),
+                        documentation: None,
+                    })
+                }
+
+                "simpleType" => {
+                    new_state = Some(ParseState::SimpleType {
+                        name: None,
+                        ty: None,
+                        item: None,
+                        values: Vec::new(),
+                        facets: Facets::default(),
+                        documentation: None,
                     })
                 }
 
-                _ => println!("FOUND {} INSIDE ELEMENT BLOCK", local_name),
+                _ => self.unsupported(local_name, "element")?,
             },
 
             Some(ParseState::ComplexType { .. }) => match local_name {
                 "sequence" => new_state = Some(ParseState::Sequence(Vec::new())),
 
-                "simpleContent" => new_state = Some(ParseState::SimpleContent{ty: None}),
+                "choice" => new_state = Some(ParseState::Choice(Vec::new())),
+
+                "simpleContent" => new_state = Some(ParseState::SimpleContent{ty: None, attributes: Vec::new()}),
 
                 "complexContent" => new_state = Some(ParseState::ComplexContent{fields: Vec::new()}),
 
-                _ => println!("FOUND {} INSIDE COMPLEX TYPE BLOCK", local_name),
+                "attribute" => new_state = Some(self.attribute_state(reader, &start, "complexType")?),
+
+                _ => self.unsupported(local_name, "complex type")?,
             },
 
             Some(ParseState::ComplexContent { .. }) => match local_name {
@@ -615,91 +1005,179 @@ impl Parser {
                     let [base] = get_attributes(reader, start.attributes(), ["base"])?;
 
                     let ty = if let Some(base) = base {
-                        self.resolve_namespace(&base)
+                        self.resolve_namespace(&base)?
                     } else {
                         unimplemented!()
                     };
 
                     let field = Field {
-                        name: self.resolve_namespace("tns:base"),
-                        ty: FieldKind::Type(ty)
+                        name: self.resolve_namespace("tns:base")?,
+                        ty: FieldKind::Type(ty),
+                        documentation: None,
                     };
 
                     new_state = Some(ParseState::ComplexExtension { fields: vec![field] });
                 },
 
-                _ => println!("FOUND {} INSIDE COMPLEX CONTENT BLOCK", local_name),
+                _ => self.unsupported(local_name, "complex content")?,
             },
 
             Some(ParseState::ComplexExtension { .. }) => match local_name {
                 "sequence" => new_state = Some(ParseState::Sequence(Vec::new())),
 
-                _ => println!("FOUND {} INSIDE COMPLEX EXTENSION BLOCK", local_name),
+                "attribute" => new_state = Some(self.attribute_state(reader, &start, "extension")?),
+
+                _ => self.unsupported(local_name, "complex extension")?,
             }
 
-            Some(ParseState::SimpleExtension { .. }) => println!("FOUND {} INSIDE SIMPLE EXTENSION BLOCK", local_name),
+            Some(ParseState::SimpleExtension { .. }) => match local_name {
+                "attribute" => new_state = Some(self.attribute_state(reader, &start, "extension")?),
+
+                _ => self.unsupported(local_name, "simple extension")?,
+            },
 
             Some(ParseState::SimpleContent { .. }) => match local_name {
                 "extension" => {
                     let [base] = get_attributes(reader, start.attributes(), ["base"])?;
 
                     let ty = if let Some(base) = base {
-                        self.resolve_namespace(&base)
+                        self.resolve_namespace(&base)?
                     } else {
                         unimplemented!()
                     };
 
-                    new_state = Some(ParseState::SimpleExtension { ty });
+                    new_state = Some(ParseState::SimpleExtension { ty, attributes: Vec::new() });
                 },
 
-                _ => println!("FOUND {} INSIDE SIMPLE CONTENT BLOCK", local_name),
+                _ => self.unsupported(local_name, "simple content")?,
             },
 
             Some(ParseState::SimpleType { .. }) => match local_name {
+                "list" => {
+                    let [item] = get_attributes(reader, start.attributes(), ["itemType"])?;
+
+                    let item = if let Some(item) = item {
+                        self.resolve_namespace(&item)?
+                    } else {
+                        return Err(error::Error::UnsupportedElement(
+                            "list without an itemType".to_owned(),
+                            "simpleType".to_owned(),
+                        ));
+                    };
+
+                    new_state = Some(ParseState::ListType { item });
+                }
+
                 "restriction" => {
                     let [base] = get_attributes(reader, start.attributes(), ["base"])?;
 
                     let ty = if let Some(base) = base {
-                        self.resolve_namespace(&base)
+                        self.resolve_namespace(&base)?
                     } else {
                         unimplemented!()
                     };
 
-                    new_state = Some(ParseState::Restriction { ty });
+                    new_state = Some(ParseState::Restriction {
+                        ty,
+                        values: Vec::new(),
+                        facets: Facets::default(),
+                    });
                 }
 
-                _ => println!("FOUND {} INSIDE SIMPLE TYPE BLOCK", local_name),
+                _ => self.unsupported(local_name, "simple type")?,
             },
 
-            Some(ParseState::Restriction { .. }) => {
-                println!("FOUND {} INSIDE RESTRICTION BLOCK", local_name)
-            }
+            Some(ParseState::Restriction { .. }) => match local_name {
+                "enumeration" => {
+                    let [value] = get_attributes(reader, start.attributes(), ["value"])?;
 
-            Some(ParseState::Sequence(_)) => match local_name {
-                "element" => {
-                    let [name, ty] = get_attributes(reader, start.attributes(), ["name", "type"])?;
+                    let value = if let Some(value) = value {
+                        value
+                    } else {
+                        unimplemented!()
+                    };
 
-                    let name = if let Some(name) = name {
-                        name
+                    new_state = Some(ParseState::Enumeration { value });
+                }
+
+                "minLength" | "maxLength" | "minInclusive" | "maxInclusive" | "pattern" => {
+                    let [value] = get_attributes(reader, start.attributes(), ["value"])?;
+
+                    let value = if let Some(value) = value {
+                        value
                     } else {
                         unimplemented!()
                     };
 
+                    new_state = Some(ParseState::Facet {
+                        name: local_name.to_owned(),
+                        value,
+                    });
+                }
+
+                _ => self.unsupported(local_name, "restriction")?,
+            },
+
+            Some(ParseState::Enumeration { .. }) => self.unsupported(local_name, "enumeration")?,
+
+            Some(ParseState::Facet { .. }) => self.unsupported(local_name, "facet")?,
+
+            Some(ParseState::ListType { .. }) => self.unsupported(local_name, "list")?,
+
+            Some(ParseState::Attribute { .. }) => self.unsupported(local_name, "attribute")?,
+
+            Some(ParseState::Sequence(_) | ParseState::Choice(_)) => match local_name {
+                "element" => {
+                    let [name, ty, reference, min_occurs, max_occurs, nillable] = get_attributes(
+                        reader,
+                        start.attributes(),
+                        ["name", "type", "ref", "minOccurs", "maxOccurs", "nillable"],
+                    )?;
+
+                    // A referenced global element becomes its own generated type
+                    let reference = reference
+                        .map(|reference| self.resolve_namespace(&reference))
+                        .transpose()?;
+
+                    let name = match (name, &reference) {
+                        (Some(name), _) => name,
+                        (None, Some(reference)) => reference.name.clone(),
+                        (None, None) => unimplemented!(),
+                    };
+
                     let ty = if let Some(ty) = ty {
-                        Some(self.resolve_namespace(&ty))
+                        Some(self.resolve_namespace(&ty)?)
                     } else {
-                        println!("{:?}", start);
-                        None
+                        reference.clone()
                     };
 
                     new_state = Some(ParseState::SequenceElement {
                         name,
+                        reference,
                         ty,
                         inner: None,
+                        min_occurs,
+                        max_occurs,
+                        nillable,
+                        documentation: None,
+                    });
+                }
+
+                // Wildcard content is captured as a single raw fragment, whatever its occurrence
+                "any" => {
+                    new_state = Some(ParseState::SequenceElement {
+                        name: local_name.to_owned(),
+                        reference: None,
+                        ty: Some(NamespacedName::new(&mut self.namespaces, XSD_NAMESPACE, "any".to_owned())),
+                        inner: None,
+                        min_occurs: None,
+                        max_occurs: None,
+                        nillable: None,
+                        documentation: None,
                     });
                 }
 
-                _ => println!("FOUND {} INSIDE SEQUENCE BLOCK", local_name),
+                _ => self.unsupported(local_name, "sequence or choice")?,
             },
 
             Some(ParseState::SequenceElement { .. }) => match local_name {
@@ -707,16 +1185,29 @@ impl Parser {
                     new_state = Some(ParseState::ComplexType {
                         kind: None,
                         name: None,
+                        attributes: Vec::new(),
+                        documentation: None,
+                    })
+                }
+
+                "simpleType" => {
+                    new_state = Some(ParseState::SimpleType {
+                        name: None,
+                        ty: None,
+                        item: None,
+                        values: Vec::new(),
+                        facets: Facets::default(),
+                        documentation: None,
                     })
                 }
 
-                _ => println!("FOUND {} INSIDE SEQUENCE ELEMENT BLOCK", local_name),
+                _ => self.unsupported(local_name, "sequence element")?,
             },
 
             Some(ParseState::Message { .. }) => match local_name {
                 "part" => {
-                    let [name, element] =
-                        get_attributes(reader, start.attributes(), ["name", "element"])?;
+                    let [name, element, ty] =
+                        get_attributes(reader, start.attributes(), ["name", "element", "type"])?;
 
                     let name = if let Some(name) = name {
                         name
@@ -724,21 +1215,24 @@ impl Parser {
                         unimplemented!()
                     };
 
-                    let element = if let Some(element) = element {
-                        self.resolve_namespace(&element)
-                    } else {
-                        unimplemented!()
+                    // Parts naming a type directly rather than a global element, as in RPC style,
+                    // are sent as an unqualified element named after the part
+                    let (name, element) = match (element, ty) {
+                        (Some(element), _) => (self.target_namespaced(name), self.resolve_namespace(&element)?),
+                        (None, Some(ty)) => (
+                            NamespacedName::new(&mut self.namespaces, "", name),
+                            self.resolve_namespace(&ty)?,
+                        ),
+                        (None, None) => unimplemented!(),
                     };
 
                     new_state = Some(ParseState::Part { name, element });
                 }
 
-                _ => println!("FOUND {} INSIDE MESSAGE BLOCK", local_name),
+                _ => self.unsupported(local_name, "message")?,
             },
 
-            Some(ParseState::Part { .. }) => match local_name {
-                _ => println!("FOUND {} INSIDE MESSAGE PATH BLOCK", local_name),
-            },
+            Some(ParseState::Part { .. }) => self.unsupported(local_name, "message part")?,
 
             Some(ParseState::PortType { .. }) => match local_name {
                 "operation" => {
@@ -755,45 +1249,80 @@ impl Parser {
                         documentation: None,
                         input: None,
                         output: None,
+                        faults: Vec::new(),
                     })
                 }
 
-                _ => println!("FOUND {} INSIDE PORT TYPE BLOCK", local_name),
+                _ => self.unsupported(local_name, "port type")?,
             },
 
             Some(ParseState::Operation { .. }) => match local_name {
-                "documentation" => new_state = Some(ParseState::Documentation(None)),
-
                 "input" | "output" => {
-                    let [message] = get_attributes(reader, start.attributes(), ["message"])?;
-
-                    let message = if let Some(message) = message {
-                        self.resolve_namespace(&message)
-                    } else {
-                        unimplemented!()
+                    let [message, element] =
+                        get_attributes(reader, start.attributes(), ["message", "element"])?;
+
+                    let message = match (message, element) {
+                        (Some(message), _) => Some(self.resolve_namespace(&message)?),
+
+                        (None, Some(element)) if element.starts_with('#') => None,
+
+                        // WSDL 2.0 references elements directly, so wrap them in a message
+                        (None, Some(element)) => {
+                            let operation = match &state {
+                                Some(ParseState::Operation { name, .. }) => name,
+                                _ => unreachable!(),
+                            };
+
+                            let suffix = if local_name == "input" { "Input" } else { "Output" };
+                            let name = self.target_namespaced(format!("{}{}", operation, suffix));
+                            let element = self.resolve_namespace(&element)?;
+                            let part = self.target_namespaced("parameters".to_owned());
+
+                            self.add_message(Message {
+                                name: name.clone(),
+                                parts: vec![Field {
+                                    name: part,
+                                    ty: FieldKind::Type(element),
+                                    documentation: None,
+                                }],
+                            });
+
+                            Some(name)
+                        }
+
+                        (None, None) => unimplemented!(),
                     };
 
-                    if local_name == "input" {
-                        new_state = Some(ParseState::Input { message })
-                    } else {
-                        new_state = Some(ParseState::Output { message })
+                    match (message, local_name) {
+                        (Some(message), "input") => new_state = Some(ParseState::Input { message }),
+                        (Some(message), _) => new_state = Some(ParseState::Output { message }),
+                        (None, _) => (),
                     }
                 }
 
-                _ => println!("FOUND {} INSIDE OPERATION BLOCK", local_name),
-            },
+                "fault" => {
+                    let [name, message] =
+                        get_attributes(reader, start.attributes(), ["name", "message"])?;
 
-            Some(ParseState::Documentation(_)) => match local_name {
-                _ => println!("FOUND {} INSIDE DOCUMENTATION BLOCK", local_name),
-            },
+                    // WSDL 2.0 interface faults are referenced by element, which isn't supported yet
+                    if let (Some(name), Some(message)) = (name, message) {
+                        let message = self.resolve_namespace(&message)?;
+                        new_state = Some(ParseState::Fault { name, message });
+                    }
+                }
 
-            Some(ParseState::Input { .. }) => match local_name {
-                _ => println!("FOUND {} INSIDE INPUT BLOCK", local_name),
+                _ => self.unsupported(local_name, "operation")?,
             },
 
-            Some(ParseState::Output { .. }) => match local_name {
-                _ => println!("FOUND {} INSIDE OUTPUT BLOCK", local_name),
-            },
+            Some(ParseState::Annotation(_)) => self.unsupported(local_name, "annotation")?,
+
+            Some(ParseState::Documentation(_)) => self.unsupported(local_name, "documentation")?,
+
+            Some(ParseState::Fault { .. }) => self.unsupported(local_name, "fault")?,
+
+            Some(ParseState::Input { .. }) => self.unsupported(local_name, "input")?,
+
+            Some(ParseState::Output { .. }) => self.unsupported(local_name, "output")?,
 
             Some(ParseState::Binding { .. }) => match local_name {
                 "binding" => {
@@ -805,34 +1334,56 @@ impl Parser {
                         unimplemented!()
                     };
 
-                    new_state = Some(ParseState::Transport { transport })
-                }
-
-                "operation" => {
-                    let [name] = get_attributes(reader, start.attributes(), ["name"])?;
-
-                    let name = if let Some(name) = name {
-                        name
+                    let version = if namespace_bytes == Some(SOAP12_NAMESPACE.as_bytes()) {
+                        SoapVersion::Soap12
                     } else {
-                        unimplemented!()
+                        SoapVersion::Soap11
                     };
 
-                    new_state = Some(ParseState::BindingOperation {
-                        name,
-                        action: None,
-                        style: None,
-                        input: None,
-                        output: None,
-                    })
+                    new_state = Some(ParseState::Transport { transport, version })
                 }
 
-                _ => println!("FOUND {} INSIDE BINDING BLOCK", local_name),
-            },
+                "operation" => {
+                    let [name, reference] = get_attributes(reader, start.attributes(), ["name", "ref"])?;
+
+                    new_state = match (name, reference) {
+                        (Some(name), _) => Some(ParseState::BindingOperation {
+                            name,
+                            action: None,
+                            style: None,
+                            input: None,
+                            output: None,
+                            namespace: None,
+                            input_headers: Vec::new(),
+                            output_headers: Vec::new(),
+                        }),
+
+                        // WSDL 2.0 binding operations refer to the interface operation
+                        (None, Some(reference)) => {
+                            let action = get_local_attribute(reader, start.attributes(), "action")?;
+                            let (_, name) = split_namespaced_name(&reference);
+
+                            Some(ParseState::BindingOperation {
+                                name: name.to_owned(),
+                                action: Some(action.unwrap_or_default()),
+                                style: Some("document".to_owned()),
+                                input: None,
+                                output: None,
+                                namespace: None,
+                                input_headers: Vec::new(),
+                                output_headers: Vec::new(),
+                            })
+                        }
+
+                        (None, None) => unimplemented!(),
+                    }
+                }
 
-            Some(ParseState::Transport { .. }) => match local_name {
-                _ => println!("FOUND {} INSIDE TRANSPORT BLOCK", local_name),
+                _ => self.unsupported(local_name, "binding")?,
             },
 
+            Some(ParseState::Transport { .. }) => self.unsupported(local_name, "transport")?,
+
             Some(ParseState::BindingOperation { .. }) => match local_name {
                 "operation" => {
                     let [action, style] =
@@ -853,20 +1404,32 @@ impl Parser {
                     new_state = Some(ParseState::OperationAction { action, style });
                 }
 
-                "input" => new_state = Some(ParseState::BindingInput { body: None }),
-                "output" => new_state = Some(ParseState::BindingOutput { body: None }),
+                "input" => {
+                    new_state = Some(ParseState::BindingInput {
+                        body: None,
+                        namespace: None,
+                        headers: Vec::new(),
+                    })
+                }
+
+                "output" => {
+                    new_state = Some(ParseState::BindingOutput {
+                        body: None,
+                        namespace: None,
+                        headers: Vec::new(),
+                    })
+                }
 
-                _ => println!("FOUND {} INSIDE BINDING OPERATION BLOCK", local_name),
+                _ => self.unsupported(local_name, "binding operation")?,
             },
 
-            Some(ParseState::OperationAction { .. }) => match local_name {
-                _ => println!("FOUND {} INSIDE OPERATION ACTION BLOCK", local_name),
-            },
+            Some(ParseState::OperationAction { .. }) => self.unsupported(local_name, "operation action")?,
 
             Some(ParseState::BindingInput { .. } | ParseState::BindingOutput { .. }) => {
                 match local_name {
                     "body" => {
-                        let [body] = get_attributes(reader, start.attributes(), ["use"])?;
+                        let [body, namespace] =
+                            get_attributes(reader, start.attributes(), ["use", "namespace"])?;
 
                         let body = if let Some(body) = body {
                             body
@@ -874,17 +1437,36 @@ impl Parser {
                             unimplemented!()
                         };
 
-                        new_state = Some(ParseState::BindingBody { body });
+                        new_state = Some(ParseState::BindingBody { body, namespace });
                     }
 
-                    _ => println!("FOUND {} INSIDE OPERATION ACTION BLOCK", local_name),
-                }
-            }
+                    "header" => {
+                        let parent = match state {
+                            Some(ParseState::BindingInput { .. }) => "input",
+                            _ => "output",
+                        };
 
-            Some(ParseState::BindingBody { .. }) => match local_name {
-                _ => println!("FOUND {} INSIDE OPERATION ACTION BLOCK", local_name),
+                        let header = self.binding_header(reader, &start, parent)?;
+                        new_state = Some(ParseState::Header { header });
+                    }
+
+                    _ => self.unsupported(local_name, "binding message")?,
+                }
+            }
+
+            Some(ParseState::Header { .. }) => match local_name {
+                "headerfault" => {
+                    let fault = self.binding_header(reader, &start, "header")?;
+                    new_state = Some(ParseState::HeaderFault { fault });
+                }
+
+                _ => self.unsupported(local_name, "binding header")?,
             },
 
+            Some(ParseState::HeaderFault { .. }) => self.unsupported(local_name, "headerfault")?,
+
+            Some(ParseState::BindingBody { .. }) => self.unsupported(local_name, "binding body")?,
+
             Some(ParseState::Service { .. }) => match local_name {
                 "port" => {
                     let [name, binding] =
@@ -897,7 +1479,7 @@ impl Parser {
                     };
 
                     let binding = if let Some(binding) = binding {
-                        self.resolve_namespace(&binding)
+                        self.resolve_namespace(&binding)?
                     } else {
                         unimplemented!()
                     };
@@ -909,7 +1491,30 @@ impl Parser {
                     });
                 }
 
-                _ => println!("FOUND {} INSIDE SERVICE BLOCK", local_name),
+                "endpoint" => {
+                    let [name, binding, address] =
+                        get_attributes(reader, start.attributes(), ["name", "binding", "address"])?;
+
+                    let name = if let Some(name) = name {
+                        name
+                    } else {
+                        unimplemented!()
+                    };
+
+                    let binding = if let Some(binding) = binding {
+                        self.resolve_namespace(&binding)?
+                    } else {
+                        unimplemented!()
+                    };
+
+                    new_state = Some(ParseState::Port {
+                        name,
+                        binding,
+                        address,
+                    });
+                }
+
+                _ => self.unsupported(local_name, "service")?,
             },
 
             Some(ParseState::Port { .. }) => match local_name {
@@ -925,17 +1530,15 @@ impl Parser {
                     new_state = Some(ParseState::Address { location })
                 }
 
-                _ => println!("FOUND {} INSIDE PORT BLOCK", local_name),
+                _ => self.unsupported(local_name, "port")?,
             },
 
-            Some(ParseState::Address { .. }) => match local_name {
-                _ => println!("FOUND {} INSIDE LOCATION BLOCK", local_name),
-            },
+            Some(ParseState::Address { .. }) => self.unsupported(local_name, "address")?,
 
-            Some(ParseState::Import { .. }) => unimplemented!(),
+            Some(ParseState::Import { .. } | ParseState::Include) => unimplemented!(),
 
             Some(ParseState::Other(ref name)) => {
-                println!("FOUND {} INSIDE {} BLOCK", local_name, name);
+                trace!("FOUND {} INSIDE {} BLOCK", local_name, name);
             }
         }
 
@@ -946,60 +1549,113 @@ impl Parser {
     }
 
     fn handle_end(&mut self, stack: &mut Vec<ParseState>) -> Result<(), error::Error> {
+        self.current_namespaces.pop_scope();
+
         let finished_state = stack.pop();
         let mut next_state = stack.pop();
 
         match finished_state {
             Some(ParseState::Definitions | ParseState::Schema) => self.pop_target_namespace(),
 
-            Some(ParseState::Element { name, kind }) => {
+            Some(ParseState::Element {
+                name,
+                kind,
+                substitution_group,
+                documentation,
+            }) => {
+                // An element without a type or inline definition can hold anything
                 let kind = if let Some(kind) = kind {
                     kind
                 } else {
-                    unimplemented!()
+                    TypeKind::Alias(NamespacedName::new(
+                        &mut self.namespaces,
+                        XSD_NAMESPACE,
+                        "anyType".to_owned(),
+                    ))
                 };
 
                 let name = self.target_namespaced(name);
-                self.definition.types.push(Type { name, kind })
-            }
 
-            Some(ParseState::ComplexType { kind, name }) => match next_state {
-                Some(ParseState::SequenceElement {
-                    ref mut ty,
-                    ref mut inner,
-                    ..
-                }) => {
-                    *ty = name.map(|name| self.target_namespaced(name));
-                    *inner = kind;
+                if let Some(head) = substitution_group {
+                    let groups = &mut self.definition.substitution_groups;
+
+                    match groups.iter_mut().find(|group| group.head == head) {
+                        Some(group) => group.members.push(name.clone()),
+                        None => groups.push(SubstitutionGroup {
+                            head,
+                            members: vec![name.clone()],
+                        }),
+                    }
                 }
 
-                Some(ParseState::Element {
-                    kind: ref mut el_kind,
-                    ..
-                }) => {
-                    if name.is_some() {
-                        unimplemented!()
+                self.add_type(Type {
+                    name,
+                    kind,
+                    documentation,
+                })?
+            }
+
+            Some(ParseState::ComplexType {
+                kind,
+                name,
+                attributes,
+                documentation,
+            }) => {
+                let kind = merge_attributes(kind, attributes);
+
+                match next_state {
+                    Some(ParseState::SequenceElement {
+                        ref mut ty,
+                        ref mut inner,
+                        documentation: ref mut element_documentation,
+                        ..
+                    }) => {
+                        *ty = name.map(|name| self.target_namespaced(name));
+                        *inner = kind;
+
+                        if element_documentation.is_none() {
+                            *element_documentation = documentation;
+                        }
                     }
 
-                    *el_kind = kind;
-                }
+                    Some(ParseState::Element {
+                        kind: ref mut el_kind,
+                        documentation: ref mut element_documentation,
+                        ..
+                    }) => {
+                        if name.is_some() {
+                            unimplemented!()
+                        }
 
-                _ => {
-                    let kind = if let Some(kind) = kind {
-                        kind
-                    } else {
-                        unimplemented!()
-                    };
+                        // The inline type replaces any type attribute, and an empty one has no fields
+                        *el_kind = Some(kind.unwrap_or_else(|| TypeKind::Struct(Vec::new())));
 
-                    let name = if let Some(name) = name {
-                        self.target_namespaced(name)
-                    } else {
-                        unimplemented!()
-                    };
+                        if element_documentation.is_none() {
+                            *element_documentation = documentation;
+                        }
+                    }
 
-                    self.definition.types.push(Type { name, kind })
+                    _ => {
+                        let kind = if let Some(kind) = kind {
+                            kind
+                        } else {
+                            unimplemented!()
+                        };
+
+                        let name = if let Some(name) = name {
+                            self.target_namespaced(name)
+                        } else {
+                            unimplemented!()
+                        };
+
+                        self.add_type(Type {
+                            name,
+                            kind,
+                            documentation,
+                        })?
+                    }
                 }
-            },
+            }
 
             Some(ParseState::ComplexContent { fields }) => match next_state {
                 Some(ParseState::ComplexType { ref mut kind, .. }) if kind.is_none() => {
@@ -1015,33 +1671,171 @@ impl Parser {
                 _ => unimplemented!()
             }
 
-            Some(ParseState::SimpleContent { ty}) => match next_state {
+            Some(ParseState::SimpleContent { ty, attributes }) => match next_state {
                 Some(ParseState::ComplexType { ref mut kind, .. }) if kind.is_none() => {
-                    *kind = Some(TypeKind::Alias(ty.unwrap()))
+                    let ty = ty.unwrap();
+
+                    // Attributes added by the extension sit alongside the text value in a struct
+                    *kind = if attributes.is_empty() {
+                        Some(TypeKind::Alias(ty))
+                    } else {
+                        let value = Field {
+                            name: self.target_namespaced("value".to_owned()),
+                            ty: FieldKind::Text(ty),
+                            documentation: None,
+                        };
+
+                        Some(TypeKind::Struct(std::iter::once(value).chain(attributes).collect()))
+                    }
                 },
 
                 _ => unimplemented!()
             }
 
-            Some(ParseState::SimpleExtension { ty: base }) => match next_state {
-                Some(ParseState::SimpleContent { ref mut ty }) => *ty = Some(base),
+            Some(ParseState::SimpleExtension { ty: base, attributes: extension }) => match next_state {
+                Some(ParseState::SimpleContent { ref mut ty, ref mut attributes }) => {
+                    *ty = Some(base);
+                    *attributes = extension;
+                }
 
                 _ => unimplemented!()
             }
 
-            Some(ParseState::SimpleType { name, ty }) => {
-                let kind = if let Some(ty) = ty {
-                    TypeKind::Simple(ty)
+            Some(ParseState::SimpleType {
+                name,
+                ty,
+                item,
+                values,
+                facets,
+                documentation,
+            }) => {
+                let kind = if !values.is_empty() {
+                    TypeKind::Enum(values)
+                } else if let Some(item) = item {
+                    TypeKind::List(item)
+                } else if let Some(ty) = ty {
+                    TypeKind::Simple(ty, facets)
                 } else {
                     unimplemented!()
                 };
 
-                let name = self.target_namespaced(name);
-                self.definition.types.push(Type { name, kind })
+                match next_state {
+                    Some(ParseState::SequenceElement {
+                        name: ref element_name,
+                        ty: ref mut element_ty,
+                        ..
+                    }) => match kind {
+                        TypeKind::Simple(base, facets) if facets.is_empty() => *element_ty = Some(base),
+
+                        // Inline enumerations and facets are generated as a type named after the element
+                        kind => {
+                            let name = self.target_namespaced(element_name.clone());
+                            self.add_type(Type {
+                                name: name.clone(),
+                                kind,
+                                documentation,
+                            })?;
+                            *element_ty = Some(name);
+                        }
+                    },
+
+                    Some(ParseState::Element {
+                        kind: ref mut element_kind,
+                        documentation: ref mut element_documentation,
+                        ..
+                    }) => {
+                        *element_kind = Some(kind);
+
+                        if element_documentation.is_none() {
+                            *element_documentation = documentation;
+                        }
+                    }
+
+                    _ => {
+                        let name = if let Some(name) = name {
+                            self.target_namespaced(name)
+                        } else {
+                            unimplemented!()
+                        };
+
+                        self.add_type(Type {
+                            name,
+                            kind,
+                            documentation,
+                        })?
+                    }
+                }
             }
 
-            Some(ParseState::Restriction { ty: base }) => match next_state {
-                Some(ParseState::SimpleType { ref mut ty, .. }) => *ty = Some(base),
+            Some(ParseState::Restriction {
+                ty: base,
+                values: restriction_values,
+                facets: restriction_facets,
+            }) => match next_state {
+                Some(ParseState::SimpleType {
+                    ref mut ty,
+                    ref mut values,
+                    ref mut facets,
+                    ..
+                }) => {
+                    *ty = Some(base);
+                    *values = restriction_values;
+                    *facets = restriction_facets;
+                }
+                _ => unimplemented!(),
+            },
+
+            Some(ParseState::Attribute {
+                name,
+                ty,
+                required,
+                documentation,
+            }) => {
+                let ty = FieldKind::Attribute(ty);
+                let field = Field {
+                    name: self.target_namespaced(name),
+                    ty: if required {
+                        ty
+                    } else {
+                        FieldKind::Optional(Box::new(ty))
+                    },
+                    documentation,
+                };
+
+                match next_state {
+                    Some(ParseState::ComplexType {
+                        ref mut attributes, ..
+                    }) => attributes.push(field),
+                    Some(
+                        ParseState::ComplexExtension { ref mut fields }
+                        | ParseState::SimpleExtension {
+                            attributes: ref mut fields,
+                            ..
+                        },
+                    ) => fields.push(field),
+                    _ => unimplemented!(),
+                }
+            }
+
+            Some(ParseState::Enumeration { value }) => match next_state {
+                Some(ParseState::Restriction { ref mut values, .. }) => values.push(value),
+                _ => unimplemented!(),
+            },
+
+            Some(ParseState::ListType { item: list_item }) => match next_state {
+                Some(ParseState::SimpleType { ref mut item, .. }) => *item = Some(list_item),
+                _ => unimplemented!(),
+            },
+
+            Some(ParseState::Facet { name, value }) => match next_state {
+                Some(ParseState::Restriction { ref mut facets, .. }) => match name.as_str() {
+                    "minLength" => facets.min_length = value.parse().ok(),
+                    "maxLength" => facets.max_length = value.parse().ok(),
+                    "minInclusive" => facets.min_inclusive = Some(value),
+                    "maxInclusive" => facets.max_inclusive = Some(value),
+                    "pattern" => facets.patterns.push(value),
+                    _ => (),
+                },
                 _ => unimplemented!(),
             },
 
@@ -1057,27 +1851,70 @@ impl Parser {
                 _ => unimplemented!(),
             },
 
-            Some(ParseState::SequenceElement { name, ty, inner }) => match next_state {
-                Some(ParseState::Sequence(ref mut fields)) => fields.push(Field {
-                    name: self.target_namespaced(name),
-                    ty: if let Some(kind) = inner {
-                        FieldKind::Inner(kind)
-                    } else {
-                        FieldKind::Type(ty.unwrap())
-                    },
-                }),
+            Some(ParseState::Choice(fields)) => match next_state {
+                Some(ParseState::ComplexType { ref mut kind, .. }) if kind.is_none() => {
+                    *kind = Some(TypeKind::Choice(fields))
+                },
+
+                _ => unimplemented!(),
+            },
+
+            Some(ParseState::SequenceElement {
+                name,
+                reference,
+                ty,
+                inner,
+                min_occurs,
+                max_occurs,
+                nillable,
+                documentation,
+            }) => match next_state {
+                Some(ParseState::Sequence(ref mut fields) | ParseState::Choice(ref mut fields)) => {
+                    // Like a global element, one without a type or inline definition can hold anything
+                    let ty = match (inner, ty) {
+                        (Some(kind), _) => FieldKind::Inner(kind),
+                        (None, Some(ty)) => FieldKind::Type(ty),
+                        (None, None) => FieldKind::Type(NamespacedName::new(
+                            &mut self.namespaces,
+                            XSD_NAMESPACE,
+                            "anyType".to_owned(),
+                        )),
+                    };
+
+                    let name = match reference {
+                        Some(reference) => reference,
+                        None => self.target_namespaced(name),
+                    };
+
+                    fields.push(Field {
+                        name,
+                        ty: match (min_occurs.as_deref(), max_occurs.as_deref()) {
+                            (_, Some("unbounded")) => FieldKind::List(Box::new(ty)),
+                            (_, Some(max)) if max.parse::<usize>().is_ok_and(|max| max > 1) => {
+                                FieldKind::List(Box::new(ty))
+                            }
+                            _ if nillable.as_deref() == Some("true") => {
+                                FieldKind::Nillable(Box::new(ty))
+                            }
+                            (Some("0"), _) => FieldKind::Optional(Box::new(ty)),
+                            _ => ty,
+                        },
+                        documentation,
+                    })
+                }
                 _ => unimplemented!(),
             },
 
             Some(ParseState::Message { name, parts }) => {
                 let name = self.target_namespaced(name);
-                self.definition.messages.push(Message { name, parts })
+                self.add_message(Message { name, parts })
             }
 
             Some(ParseState::Part { name, element }) => match next_state {
                 Some(ParseState::Message { ref mut parts, .. }) => parts.push(Field {
-                    name: self.target_namespaced(name),
+                    name,
                     ty: FieldKind::Type(element),
+                    documentation: None,
                 }),
                 _ => unimplemented!(),
             },
@@ -1094,24 +1931,60 @@ impl Parser {
                 input,
                 output,
                 documentation,
+                faults,
             }) => match next_state {
                 Some(ParseState::PortType {
                     ref mut operations, ..
-                }) => operations.push(Operation {
-                    name: self.target_namespaced(name),
-                    input,
-                    output,
-                    documentation,
-                }),
+                }) => {
+                    let operation = Operation {
+                        name: self.target_namespaced(name),
+                        input,
+                        output,
+                        documentation,
+                        faults,
+                    };
+
+                    self.visitor.visit_operation(&operation);
+                    operations.push(operation)
+                }
                 _ => unimplemented!(),
             },
 
             Some(ParseState::Documentation(text)) => match next_state {
-                Some(ParseState::Operation {
-                    ref mut documentation,
-                    ..
-                }) => *documentation = text,
-                _ => unimplemented!(),
+                Some(
+                    ParseState::Operation {
+                        ref mut documentation,
+                        ..
+                    }
+                    | ParseState::Annotation(ref mut documentation),
+                ) => *documentation = text,
+                _ => (),
+            },
+
+            Some(ParseState::Annotation(text)) => match next_state {
+                Some(
+                    ParseState::Element {
+                        ref mut documentation,
+                        ..
+                    }
+                    | ParseState::ComplexType {
+                        ref mut documentation,
+                        ..
+                    }
+                    | ParseState::SimpleType {
+                        ref mut documentation,
+                        ..
+                    }
+                    | ParseState::SequenceElement {
+                        ref mut documentation,
+                        ..
+                    }
+                    | ParseState::Attribute {
+                        ref mut documentation,
+                        ..
+                    },
+                ) => *documentation = text,
+                _ => (),
             },
 
             Some(ParseState::Input { message }) => match next_state {
@@ -1128,10 +2001,25 @@ impl Parser {
                 _ => unimplemented!(),
             },
 
-            Some(ParseState::Transport { transport: kind }) => match next_state {
+            Some(ParseState::Fault { name, message }) => match next_state {
+                Some(ParseState::Operation { ref mut faults, .. }) => {
+                    faults.push(Fault { name, message })
+                }
+                _ => unimplemented!(),
+            },
+
+            Some(ParseState::Transport {
+                transport: kind,
+                version: binding_version,
+            }) => match next_state {
                 Some(ParseState::Binding {
-                    ref mut transport, ..
-                }) if transport.is_none() => *transport = Some(kind),
+                    ref mut transport,
+                    ref mut version,
+                    ..
+                }) if transport.is_none() => {
+                    *transport = Some(kind);
+                    *version = binding_version;
+                }
                 _ => unimplemented!(),
             },
 
@@ -1139,6 +2027,7 @@ impl Parser {
                 name,
                 ty,
                 transport,
+                version,
                 operations,
             }) => {
                 let name = self.target_namespaced(name);
@@ -1146,6 +2035,7 @@ impl Parser {
                     name,
                     ty,
                     transport: transport.unwrap(),
+                    version,
                     operations,
                 })
             }
@@ -1156,6 +2046,9 @@ impl Parser {
                 style,
                 input,
                 output,
+                namespace,
+                input_headers,
+                output_headers,
             }) => match next_state {
                 Some(ParseState::Binding {
                     ref mut operations, ..
@@ -1165,6 +2058,9 @@ impl Parser {
                     style: style.unwrap(),
                     input,
                     output,
+                    namespace,
+                    input_headers,
+                    output_headers,
                 }),
                 _ => unimplemented!(),
             },
@@ -1181,27 +2077,78 @@ impl Parser {
                 _ => unimplemented!(),
             },
 
-            Some(ParseState::BindingInput { body }) => match next_state {
-                Some(ParseState::BindingOperation { ref mut input, .. }) => *input = body,
+            Some(ParseState::BindingInput {
+                body,
+                namespace: body_namespace,
+                headers,
+            }) => match next_state {
+                Some(ParseState::BindingOperation {
+                    ref mut input,
+                    ref mut namespace,
+                    ref mut input_headers,
+                    ..
+                }) => {
+                    *input = body;
+                    *namespace = body_namespace;
+                    *input_headers = headers;
+                }
                 _ => unimplemented!(),
             },
 
-            Some(ParseState::BindingOutput { body }) => match next_state {
-                Some(ParseState::BindingOperation { ref mut output, .. }) => *output = body,
+            Some(ParseState::BindingOutput { body, headers, .. }) => match next_state {
+                Some(ParseState::BindingOperation {
+                    ref mut output,
+                    ref mut output_headers,
+                    ..
+                }) => {
+                    *output = body;
+                    *output_headers = headers;
+                }
                 _ => unimplemented!(),
             },
 
-            Some(ParseState::BindingBody { body: body_use }) => match next_state {
+            Some(ParseState::Header { header }) => match next_state {
                 Some(
-                    ParseState::BindingInput { ref mut body }
-                    | ParseState::BindingOutput { ref mut body },
-                ) => *body = Some(body_use),
+                    ParseState::BindingInput {
+                        ref mut headers, ..
+                    }
+                    | ParseState::BindingOutput {
+                        ref mut headers, ..
+                    },
+                ) => headers.push(header),
+                _ => unimplemented!(),
+            },
+
+            Some(ParseState::HeaderFault { fault }) => match next_state {
+                Some(ParseState::Header { ref mut header }) => header.faults.push(fault),
+                _ => unimplemented!(),
+            },
+
+            Some(ParseState::BindingBody {
+                body: body_use,
+                namespace: body_namespace,
+            }) => match next_state {
+                Some(
+                    ParseState::BindingInput {
+                        ref mut body,
+                        ref mut namespace,
+                        ..
+                    }
+                    | ParseState::BindingOutput {
+                        ref mut body,
+                        ref mut namespace,
+                        ..
+                    },
+                ) => {
+                    *body = Some(body_use);
+                    *namespace = body_namespace;
+                }
                 _ => unimplemented!(),
             },
 
             Some(ParseState::Service { name, ports }) => {
                 let name = self.target_namespaced(name);
-                self.definition.services.push(Service { name, ports })
+                self.add_service(Service { name, ports })
             }
 
             Some(ParseState::Port {
@@ -1231,26 +2178,1050 @@ impl Parser {
         Ok(())
     }
 
-    fn handle_text<'a, B: BufRead>(
+    fn handle_text<B: BufRead>(
         &mut self,
-        stack: &mut Vec<ParseState>,
+        stack: &mut [ParseState],
         reader: &Reader<B>,
-        start: BytesText<'a>,
+        start: BytesText<'_>,
     ) -> Result<(), error::Error> {
         let unescaped = start.unescaped()?;
         let text = reader.decode(unescaped.as_ref())?;
-        let mut state = stack.pop();
-
-        match state {
-            Some(ParseState::Documentation(ref mut docs)) => *docs = Some(text.to_owned()),
-            _ => (),
-        }
 
-        stack.extend(state);
+        append_text(stack, text);
         Ok(())
     }
 }
 
-pub fn parse(url: Url) -> Result<(Definition, Namespaces), error::Error> {
-    Parser::new(url).parse()
+fn append_text(stack: &mut [ParseState], text: &str) {
+    if let Some(ParseState::Documentation(docs)) = stack.last_mut() {
+        docs.get_or_insert_with(String::new).push_str(text);
+    }
+}
+
+pub fn parse(
+    url: Url,
+    resolver: &dyn Resolver,
+    visitor: &mut dyn Visitor,
+) -> Result<(Definition, Namespaces), error::Error> {
+    Parser::new(url, resolver, visitor).parse()
+}
+
+pub fn parse_with_options(
+    url: Url,
+    resolver: &dyn Resolver,
+    visitor: &mut dyn Visitor,
+    options: ParseOptions,
+) -> Result<(Definition, Namespaces, Vec<Unsupported>), error::Error> {
+    let mut parser = Parser::new(url, resolver, visitor);
+    parser.strict = options.strict;
+    parser.parse_url(parser.root.clone())?;
+
+    Ok((parser.definition, parser.namespaces, parser.unsupported))
+}
+
+fn accepts_documentation(state: &ParseState) -> bool {
+    !matches!(state, ParseState::Documentation(_) | ParseState::Other(_))
+}
+
+// Documentation doesn't change how a type is generated, so it is ignored when comparing
+fn structure(kind: &TypeKind) -> TypeKind {
+    let fields = |fields: &[Field]| {
+        fields
+            .iter()
+            .map(|field| Field {
+                name: field.name.clone(),
+                ty: field_structure(&field.ty),
+                documentation: None,
+            })
+            .collect()
+    };
+
+    match kind {
+        TypeKind::Struct(inner) => TypeKind::Struct(fields(inner)),
+        TypeKind::Choice(inner) => TypeKind::Choice(fields(inner)),
+        kind => kind.clone(),
+    }
+}
+
+fn field_structure(kind: &FieldKind) -> FieldKind {
+    match kind {
+        FieldKind::Inner(inner) => FieldKind::Inner(structure(inner)),
+        FieldKind::Optional(inner) => FieldKind::Optional(Box::new(field_structure(inner))),
+        FieldKind::List(inner) => FieldKind::List(Box::new(field_structure(inner))),
+        FieldKind::Nillable(inner) => FieldKind::Nillable(Box::new(field_structure(inner))),
+        kind => kind.clone(),
+    }
+}
+
+pub fn parse_str(
+    contents: &str,
+    base: Url,
+    resolver: &dyn Resolver,
+    visitor: &mut dyn Visitor,
+) -> Result<(Definition, Namespaces), error::Error> {
+    Parser::new(base, resolver, visitor).parse_str(contents)
+}
+
+pub fn parse_reader<R: BufRead>(
+    reader: R,
+    base: Url,
+    resolver: &dyn Resolver,
+    visitor: &mut dyn Visitor,
+) -> Result<(Definition, Namespaces), error::Error> {
+    Parser::new(base, resolver, visitor).parse_reader(reader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        resolver::{DefaultResolver, DirectoryResolver},
+        visitor::DefaultVisitor,
+    };
+
+    fn definitions(body: &str) -> String {
+        format!(
+            r#"<?xml version="1.0"?>
+            <definitions xmlns="http://schemas.xmlsoap.org/wsdl/"
+                         xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+                         xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+                         xmlns:tns="http://example.com/test"
+                         targetNamespace="http://example.com/test">{}</definitions>"#,
+            body
+        )
+    }
+
+    fn try_parse(body: &str) -> Result<(Definition, Namespaces), error::Error> {
+        parse_str(&definitions(body), Url::parse("http://example.com/").unwrap(), &DefaultResolver, &mut DefaultVisitor)
+    }
+
+    fn try_parse_with_files(
+        name: &str,
+        files: &[(&str, &str)],
+        body: &str,
+    ) -> Result<(Definition, Namespaces), error::Error> {
+        in_directory(name, files, |base| parse_str(&definitions(body), base, &DefaultResolver, &mut DefaultVisitor))
+    }
+
+    // Writes the imported files to a directory the definitions are resolved relative to
+    fn in_directory<T>(name: &str, files: &[(&str, &str)], parse: impl FnOnce(Url) -> T) -> T {
+        let directory = std::env::temp_dir().join(format!("suds-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        for (file, contents) in files {
+            let path = directory.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+
+        let result = parse(Url::from_directory_path(&directory).unwrap());
+        std::fs::remove_dir_all(directory).unwrap();
+        result
+    }
+
+    fn parse_strict(body: &str) -> Result<(Definition, Namespaces), crate::error::Error> {
+        let mut visitor = crate::visitor::DefaultVisitor;
+        let mut parser = super::Parser::new(
+            Url::parse("http://example.com/").unwrap(),
+            &crate::resolver::DefaultResolver,
+            &mut visitor,
+        );
+
+        parser.strict = true;
+        parser.parse_str(&definitions(body))
+    }
+
+    fn point_schemas(first: &str, second: &str) -> String {
+        format!(
+            r#"<types>
+                <xsd:schema targetNamespace="http://example.com/test">{}</xsd:schema>
+                <xsd:schema targetNamespace="http://example.com/test">{}</xsd:schema>
+            </types>"#,
+            first, second
+        )
+    }
+
+    fn namespace<'a>(namespaces: &'a Namespaces, name: &NamespacedName) -> &'a str {
+        &namespaces.namespaces()[name.index()]
+    }
+
+    #[test]
+    fn attribute_references_are_unsupported() {
+        let result = try_parse(
+            r#"<types>
+                <xsd:schema targetNamespace="http://example.com/test">
+                    <xsd:complexType name="Note">
+                        <xsd:attribute ref="tns:lang"/>
+                    </xsd:complexType>
+                </xsd:schema>
+            </types>"#,
+        );
+
+        assert!(matches!(
+            result,
+            Err(error::Error::UnsupportedElement(element, parent)) if element == "attribute without a name" && parent == "complexType"
+        ));
+    }
+
+    #[test]
+    fn included_schemas_share_the_target_namespace() {
+        let (definition, namespaces) = try_parse_with_files(
+            "include",
+            &[(
+                "common.xsd",
+                r#"<xsd:schema xmlns:xsd="http://www.w3.org/2001/XMLSchema">
+                    <xsd:complexType name="Shared">
+                        <xsd:sequence>
+                            <xsd:element name="id" type="xsd:int"/>
+                        </xsd:sequence>
+                    </xsd:complexType>
+                </xsd:schema>"#,
+            )],
+            r#"<types>
+                <xsd:schema targetNamespace="http://example.com/test">
+                    <xsd:include schemaLocation="common.xsd"/>
+                </xsd:schema>
+            </types>"#,
+        )
+        .unwrap();
+
+        let shared = definition.types.iter().find(|ty| ty.name.name == "Shared").unwrap();
+        assert_eq!(namespace(&namespaces, &shared.name), "http://example.com/test");
+    }
+
+    #[test]
+    fn circular_imports_terminate() {
+        let schema = |namespace: &str, name: &str, other: &str, location: &str| {
+            format!(
+                r#"<xsd:schema xmlns:xsd="http://www.w3.org/2001/XMLSchema" targetNamespace="{}">
+                    <xsd:import namespace="{}" schemaLocation="{}"/>
+                    <xsd:simpleType name="{}">
+                        <xsd:restriction base="xsd:string"></xsd:restriction>
+                    </xsd:simpleType>
+                </xsd:schema>"#,
+                namespace, other, location, name
+            )
+        };
+
+        let (definition, _) = try_parse_with_files(
+            "circular",
+            &[
+                ("a.xsd", &schema("urn:a", "First", "urn:b", "b.xsd")),
+                ("b.xsd", &schema("urn:b", "Second", "urn:a", "a.xsd")),
+            ],
+            r#"<types>
+                <xsd:schema targetNamespace="http://example.com/test">
+                    <xsd:import namespace="urn:a" schemaLocation="a.xsd"/>
+                </xsd:schema>
+            </types>"#,
+        )
+        .unwrap();
+
+        let mut names = definition.types.iter().map(|ty| ty.name.name.as_str()).collect::<Vec<_>>();
+        names.sort_unstable();
+        assert_eq!(names, ["First", "Second"]);
+    }
+
+    #[test]
+    fn remote_imports_resolve_to_local_files() {
+        let body = r#"<types>
+            <xsd:schema targetNamespace="http://example.com/test">
+                <xsd:import namespace="urn:shared" schemaLocation="https://schemas.example.com/common/shared.xsd"/>
+            </xsd:schema>
+        </types>"#;
+
+        let result = in_directory(
+            "offline",
+            &[(
+                "schemas.example.com/common/shared.xsd",
+                r#"<xsd:schema xmlns:xsd="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:shared">
+                    <xsd:simpleType name="Shared">
+                        <xsd:restriction base="xsd:string"></xsd:restriction>
+                    </xsd:simpleType>
+                </xsd:schema>"#,
+            )],
+            |base| {
+                let resolver = DirectoryResolver::new(base.to_file_path().unwrap());
+                parse_str(&definitions(body), base, &resolver, &mut DefaultVisitor)
+            },
+        );
+
+        let (definition, namespaces) = result.unwrap();
+        let shared = definition.types.iter().find(|ty| ty.name.name == "Shared").unwrap();
+        assert_eq!(namespace(&namespaces, &shared.name), "urn:shared");
+    }
+
+    #[test]
+    fn calculator_from_a_string() {
+        const CALCULATOR: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/" xmlns:tns="http://tempuri.org/" xmlns:s="http://www.w3.org/2001/XMLSchema" targetNamespace="http://tempuri.org/" xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/">
+  <wsdl:types>
+    <s:schema elementFormDefault="qualified" targetNamespace="http://tempuri.org/">
+      <s:element name="Add">
+        <s:complexType>
+          <s:sequence>
+            <s:element minOccurs="1" maxOccurs="1" name="intA" type="s:int" />
+            <s:element minOccurs="1" maxOccurs="1" name="intB" type="s:int" />
+          </s:sequence>
+        </s:complexType>
+      </s:element>
+      <s:element name="AddResponse">
+        <s:complexType>
+          <s:sequence>
+            <s:element minOccurs="1" maxOccurs="1" name="AddResult" type="s:int" />
+          </s:sequence>
+        </s:complexType>
+      </s:element>
+    </s:schema>
+  </wsdl:types>
+  <wsdl:message name="AddSoapIn">
+    <wsdl:part name="parameters" element="tns:Add" />
+  </wsdl:message>
+  <wsdl:message name="AddSoapOut">
+    <wsdl:part name="parameters" element="tns:AddResponse" />
+  </wsdl:message>
+  <wsdl:portType name="CalculatorSoap">
+    <wsdl:operation name="Add">
+      <wsdl:documentation xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/">Adds two integers. This is a test WebService. ©DNE Online</wsdl:documentation>
+      <wsdl:input message="tns:AddSoapIn" />
+      <wsdl:output message="tns:AddSoapOut" />
+    </wsdl:operation>
+  </wsdl:portType>
+  <wsdl:binding name="CalculatorSoap" type="tns:CalculatorSoap">
+    <soap:binding transport="http://schemas.xmlsoap.org/soap/http" />
+    <wsdl:operation name="Add">
+      <soap:operation soapAction="http://tempuri.org/Add" style="document" />
+      <wsdl:input>
+        <soap:body use="literal" />
+      </wsdl:input>
+      <wsdl:output>
+        <soap:body use="literal" />
+      </wsdl:output>
+    </wsdl:operation>
+  </wsdl:binding>
+  <wsdl:service name="Calculator">
+    <wsdl:port name="CalculatorSoap" binding="tns:CalculatorSoap">
+      <soap:address location="http://www.dneonline.com/calculator.asmx" />
+    </wsdl:port>
+  </wsdl:service>
+</wsdl:definitions>"#;
+
+        let (definition, _) =
+            crate::parse_str(CALCULATOR, Url::parse("http://www.dneonline.com/calculator.asmx").unwrap()).unwrap();
+
+        assert_eq!(definition.types.len(), 2);
+        assert_eq!(definition.messages.len(), 2);
+        assert_eq!(definition.bindings[0].operations[0].action, "http://tempuri.org/Add");
+        assert_eq!(definition.services[0].name.name, "Calculator");
+        assert_eq!(definition.services[0].ports[0].location, "http://www.dneonline.com/calculator.asmx");
+    }
+
+    #[test]
+    fn wsdl_2_descriptions() {
+        let (definition, _) = parse_str(
+            r#"<description xmlns="http://www.w3.org/ns/wsdl"
+                            xmlns:wsoap="http://www.w3.org/ns/wsdl/soap"
+                            xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+                            xmlns:tns="http://example.com/test"
+                            targetNamespace="http://example.com/test">
+                <types>
+                    <xsd:schema targetNamespace="http://example.com/test">
+                        <xsd:element name="Ping" type="xsd:string"/>
+                        <xsd:element name="PingResponse" type="xsd:string"/>
+                    </xsd:schema>
+                </types>
+                <interface name="PingInterface">
+                    <operation name="Ping" pattern="http://www.w3.org/ns/wsdl/in-out">
+                        <input element="tns:Ping"/>
+                        <output element="tns:PingResponse"/>
+                    </operation>
+                </interface>
+                <binding name="PingBinding" interface="tns:PingInterface"
+                         type="http://www.w3.org/ns/wsdl/soap"
+                         wsoap:protocol="http://www.w3.org/2003/05/soap/bindings/HTTP/">
+                    <operation ref="tns:Ping" wsoap:action="urn:ping"/>
+                </binding>
+                <service name="PingService" interface="tns:PingInterface">
+                    <endpoint name="PingEndpoint" binding="tns:PingBinding" address="http://example.com/ping"/>
+                </service>
+            </description>"#,
+            Url::parse("http://example.com/").unwrap(),
+            &DefaultResolver,
+            &mut DefaultVisitor,
+        )
+        .unwrap();
+
+        let operation = &definition.port_types[0].operations[0];
+        assert_eq!(operation.name.name, "Ping");
+        assert_eq!(operation.input.as_ref().unwrap().name, "PingInput");
+        assert_eq!(operation.output.as_ref().unwrap().name, "PingOutput");
+        assert_eq!(definition.messages.len(), 2);
+
+        assert_eq!(definition.bindings[0].operations[0].action, "urn:ping");
+        assert_eq!(definition.services[0].ports[0].name.name, "PingEndpoint");
+        assert_eq!(definition.services[0].ports[0].location, "http://example.com/ping");
+    }
+
+    #[test]
+    fn schema_documentation() {
+        let (definition, _) = try_parse(
+            r#"<types>
+                <xsd:schema targetNamespace="http://example.com/test">
+                    <xsd:complexType name="Station">
+                        <xsd:annotation><xsd:documentation>A railway station</xsd:documentation></xsd:annotation>
+                        <xsd:sequence>
+                            <xsd:element name="crs" type="xsd:string">
+                                <xsd:annotation><xsd:documentation>The three letter station code</xsd:documentation></xsd:annotation>
+                            </xsd:element>
+                        </xsd:sequence>
+                    </xsd:complexType>
+                </xsd:schema>
+            </types>"#,
+        )
+        .unwrap();
+
+        let station = &definition.types[0];
+        assert_eq!(station.documentation.as_deref(), Some("A railway station"));
+
+        match &station.kind {
+            TypeKind::Struct(fields) => {
+                assert_eq!(fields[0].documentation.as_deref(), Some("The three letter station code"))
+            }
+            kind => panic!("unexpected {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn list_types() {
+        let (definition, namespaces) = try_parse(
+            r#"<types>
+                <xsd:schema targetNamespace="http://example.com/test">
+                    <xsd:simpleType name="Ids">
+                        <xsd:list itemType="xsd:int"/>
+                    </xsd:simpleType>
+                </xsd:schema>
+            </types>"#,
+        )
+        .unwrap();
+
+        match &definition.types[0].kind {
+            TypeKind::List(item) => {
+                assert_eq!(item.name, "int");
+                assert_eq!(namespace(&namespaces, item), "http://www.w3.org/2001/XMLSchema");
+            }
+            kind => panic!("unexpected {:?}", kind),
+        }
+
+        let result = try_parse(
+            r#"<types>
+                <xsd:schema targetNamespace="http://example.com/test">
+                    <xsd:simpleType name="Ids">
+                        <xsd:list>
+                            <xsd:simpleType><xsd:restriction base="xsd:int"/></xsd:simpleType>
+                        </xsd:list>
+                    </xsd:simpleType>
+                </xsd:schema>
+            </types>"#,
+        );
+
+        assert!(matches!(
+            result,
+            Err(error::Error::UnsupportedElement(element, parent)) if element == "list without an itemType" && parent == "simpleType"
+        ));
+    }
+
+    #[test]
+    fn substitution_groups() {
+        let (definition, _) = try_parse(
+            r#"<types>
+                <xsd:schema targetNamespace="http://example.com/test">
+                    <xsd:element name="Vehicle" type="xsd:string"/>
+                    <xsd:element name="Car" type="xsd:string" substitutionGroup="tns:Vehicle"/>
+                    <xsd:element name="Bike" type="xsd:string" substitutionGroup="tns:Vehicle"/>
+                </xsd:schema>
+            </types>"#,
+        )
+        .unwrap();
+
+        let groups = &definition.substitution_groups;
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].head.name, "Vehicle");
+        assert_eq!(
+            groups[0].members.iter().map(|member| member.name.as_str()).collect::<Vec<_>>(),
+            ["Car", "Bike"]
+        );
+    }
+
+    fn binding_with_input(input: &str) -> String {
+        format!(
+            r#"<binding name="PingBinding" type="tns:PingPortType">
+                <soap:binding style="document" transport="http://schemas.xmlsoap.org/soap/http"/>
+                <operation name="Ping">
+                    <soap:operation soapAction="http://example.com/test/Ping" style="document"/>
+                    <input>{}</input>
+                    <output><soap:body use="literal"/></output>
+                </operation>
+            </binding>"#,
+            input
+        )
+    }
+
+    #[test]
+    fn binding_headers() {
+        let (definition, _) = try_parse(&binding_with_input(
+            r#"<soap:body use="literal"/>
+            <soap:header message="tns:AuthHeader" part="token" use="literal">
+                <soap:headerfault message="tns:AuthFault" part="reason" use="literal"/>
+            </soap:header>"#,
+        ))
+        .unwrap();
+
+        let operation = &definition.bindings[0].operations[0];
+        assert!(operation.output_headers.is_empty());
+
+        let header = &operation.input_headers[0];
+        assert_eq!(header.message.name, "AuthHeader");
+        assert_eq!(header.part, "token");
+        assert_eq!(header.body.as_deref(), Some("literal"));
+        assert_eq!(header.faults[0].message.name, "AuthFault");
+        assert_eq!(header.faults[0].part, "reason");
+
+        let result = try_parse(&binding_with_input(
+            r#"<soap:body use="literal"/><soap:header message="tns:AuthHeader" use="literal"/>"#,
+        ));
+
+        assert!(matches!(
+            result,
+            Err(error::Error::UnsupportedElement(element, parent)) if element == "header without a message and part" && parent == "input"
+        ));
+    }
+
+    #[test]
+    fn operation_faults() {
+        let (definition, _) = try_parse(
+            r#"<portType name="PingPortType">
+                <operation name="Ping">
+                    <input message="tns:PingRequest"/>
+                    <output message="tns:PingResponse"/>
+                    <fault name="InvalidPing" message="tns:InvalidPingFault"/>
+                </operation>
+            </portType>"#,
+        )
+        .unwrap();
+
+        let faults = &definition.port_types[0].operations[0].faults;
+        assert_eq!(faults.len(), 1);
+        assert_eq!(faults[0].name, "InvalidPing");
+        assert_eq!(faults[0].message.name, "InvalidPingFault");
+    }
+
+    #[test]
+    fn default_namespace_declarations() {
+        let (definition, namespaces) = try_parse(
+            r#"<types>
+                <schema xmlns="http://www.w3.org/2001/XMLSchema" targetNamespace="http://example.com/test">
+                    <complexType name="Point">
+                        <sequence>
+                            <element name="x" type="int"/>
+                        </sequence>
+                    </complexType>
+                </schema>
+            </types>"#,
+        )
+        .unwrap();
+
+        assert_eq!(namespace(&namespaces, &definition.types[0].name), "http://example.com/test");
+
+        match &definition.types[0].kind {
+            TypeKind::Struct(fields) => match &fields[0].ty {
+                FieldKind::Type(ty) => {
+                    assert_eq!(ty.name, "int");
+                    assert_eq!(namespace(&namespaces, ty), "http://www.w3.org/2001/XMLSchema");
+                }
+                ty => panic!("unexpected {:?}", ty),
+            },
+            kind => panic!("unexpected {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn root_prefixes_are_visible_throughout() {
+        // xsd is only declared on the definitions element
+        let (definition, namespaces) = try_parse(
+            r#"<types>
+                <xsd:schema targetNamespace="http://example.com/test">
+                    <xsd:complexType name="Line">
+                        <xsd:sequence>
+                            <xsd:element name="length" type="xsd:int"/>
+                        </xsd:sequence>
+                    </xsd:complexType>
+                </xsd:schema>
+            </types>"#,
+        )
+        .unwrap();
+
+        match &definition.types[0].kind {
+            TypeKind::Struct(fields) => assert!(
+                matches!(&fields[0].ty, FieldKind::Type(ty) if namespace(&namespaces, ty) == "http://www.w3.org/2001/XMLSchema")
+            ),
+            kind => panic!("unexpected {:?}", kind),
+        }
+
+        let result = try_parse(
+            r#"<message name="PingRequest">
+                <part name="value" element="undeclared:Ping"/>
+            </message>"#,
+        );
+
+        assert!(matches!(result, Err(error::Error::UnknownNamespacePrefix(prefix)) if prefix == "undeclared"));
+    }
+
+    #[test]
+    fn shadowed_prefixes_resolve_to_the_innermost_declaration() {
+        let (definition, namespaces) = try_parse(
+            r#"<types xmlns:p="urn:outer">
+                <xsd:schema xmlns:p="urn:inner" targetNamespace="http://example.com/test">
+                    <xsd:complexType name="Holder">
+                        <xsd:sequence>
+                            <xsd:element name="inner" type="p:Thing"/>
+                        </xsd:sequence>
+                    </xsd:complexType>
+                </xsd:schema>
+                <xsd:schema targetNamespace="http://example.com/test">
+                    <xsd:complexType name="Other">
+                        <xsd:sequence>
+                            <xsd:element name="outer" type="p:Thing"/>
+                        </xsd:sequence>
+                    </xsd:complexType>
+                </xsd:schema>
+            </types>"#,
+        )
+        .unwrap();
+
+        let field_namespace = |name: &str| match &definition.types.iter().find(|ty| ty.name.name == name).unwrap().kind {
+            TypeKind::Struct(fields) => match &fields[0].ty {
+                FieldKind::Type(ty) => namespace(&namespaces, ty).to_owned(),
+                ty => panic!("unexpected {:?}", ty),
+            },
+            kind => panic!("unexpected {:?}", kind),
+        };
+
+        assert_eq!(field_namespace("Holder"), "urn:inner");
+        assert_eq!(field_namespace("Other"), "urn:outer");
+
+        // A prefix declared on one schema is gone once that schema ends
+        let result = try_parse(
+            r#"<types>
+                <xsd:schema xmlns:q="urn:first" targetNamespace="http://example.com/test"/>
+                <xsd:schema targetNamespace="http://example.com/test">
+                    <xsd:element name="Late" type="q:Thing"/>
+                </xsd:schema>
+            </types>"#,
+        );
+
+        assert!(matches!(result, Err(error::Error::UnknownNamespacePrefix(prefix)) if prefix == "q"));
+    }
+
+    #[test]
+    fn simple_content_extensions_keep_their_attributes() {
+        let (definition, _) = try_parse(
+            r#"<types>
+                <xsd:schema targetNamespace="http://example.com/test">
+                    <xsd:complexType name="Amount">
+                        <xsd:simpleContent>
+                            <xsd:extension base="xsd:decimal">
+                                <xsd:attribute name="currency" type="xsd:string"/>
+                            </xsd:extension>
+                        </xsd:simpleContent>
+                    </xsd:complexType>
+                </xsd:schema>
+            </types>"#,
+        )
+        .unwrap();
+
+        match &definition.types[0].kind {
+            TypeKind::Struct(fields) => {
+                assert!(matches!(&fields[0].ty, FieldKind::Text(ty) if ty.name == "decimal"));
+                assert_eq!(fields[1].name.name, "currency");
+                assert!(matches!(&fields[1].ty, FieldKind::Optional(inner) if matches!(**inner, FieldKind::Attribute(_))));
+            }
+            kind => panic!("unexpected {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn visitor_sees_each_item() {
+        #[derive(Default)]
+        struct Recorder(Vec<String>);
+
+        impl crate::visitor::Visitor for Recorder {
+            fn visit_type(&mut self, ty: &Type) {
+                self.0.push(format!("type {}", ty.name.name));
+            }
+
+            fn visit_message(&mut self, message: &Message) {
+                self.0.push(format!("message {}", message.name.name));
+            }
+
+            fn visit_operation(&mut self, operation: &Operation) {
+                self.0.push(format!("operation {}", operation.name.name));
+            }
+
+            fn visit_service(&mut self, service: &Service) {
+                self.0.push(format!("service {}", service.name.name));
+            }
+        }
+
+        let mut recorder = Recorder::default();
+
+        super::parse_str(
+            &definitions(
+                r#"<types>
+                    <xsd:schema targetNamespace="http://example.com/test">
+                        <xsd:simpleType name="Code">
+                            <xsd:restriction base="xsd:string"></xsd:restriction>
+                        </xsd:simpleType>
+                    </xsd:schema>
+                </types>
+                <message name="PingRequest">
+                    <part name="code" element="tns:Code"/>
+                </message>
+                <portType name="PingPortType">
+                    <operation name="Ping">
+                        <input message="tns:PingRequest"/>
+                    </operation>
+                </portType>
+                <service name="PingService"></service>"#,
+            ),
+            Url::parse("http://example.com/").unwrap(),
+            &DefaultResolver,
+            &mut recorder,
+        )
+        .unwrap();
+
+        assert_eq!(
+            recorder.0,
+            ["type Code", "message PingRequest", "operation Ping", "service PingService"]
+        );
+    }
+
+    #[test]
+    fn utf16_with_a_bom() {
+        let body = r#"<message name="PingRequest">
+            <part name="value" element="tns:Ping"/>
+        </message>"#;
+
+        let utf8 = format!(r#"<?xml version="1.0" encoding="UTF-8"?>{}"#, definitions(body));
+        let utf16 = format!(r#"<?xml version="1.0" encoding="UTF-16"?>{}"#, definitions(body));
+
+        let bytes = "\u{feff}"
+            .encode_utf16()
+            .chain(utf16.encode_utf16())
+            .flat_map(u16::to_le_bytes)
+            .collect::<Vec<_>>();
+
+        let (expected, _) = parse_str(&utf8, Url::parse("http://example.com/").unwrap(), &DefaultResolver, &mut DefaultVisitor).unwrap();
+        let (definition, _) = in_directory("utf16", &[], |base| {
+            let url = base.join("service.wsdl").unwrap();
+            std::fs::write(url.to_file_path().unwrap(), &bytes).unwrap();
+            super::parse(url, &DefaultResolver, &mut DefaultVisitor)
+        })
+        .unwrap();
+
+        assert_eq!(format!("{:?}", definition), format!("{:?}", expected));
+    }
+
+    #[test]
+    fn elements_with_inline_complex_types() {
+        let (definition, namespaces) = try_parse(
+            r#"<types>
+                <xsd:schema targetNamespace="http://example.com/test">
+                    <xsd:element name="Ping" type="tns:Ping">
+                        <xsd:complexType>
+                            <xsd:sequence>
+                                <xsd:element name="value" type="xsd:string"/>
+                            </xsd:sequence>
+                        </xsd:complexType>
+                    </xsd:element>
+                    <xsd:element name="Anything"/>
+                </xsd:schema>
+            </types>"#,
+        )
+        .unwrap();
+
+        assert_eq!(definition.types.len(), 2);
+        assert_eq!(definition.types[0].name.name, "Ping");
+
+        match &definition.types[0].kind {
+            TypeKind::Struct(fields) => {
+                assert_eq!(fields.len(), 1);
+                assert_eq!(fields[0].name.name, "value");
+            }
+            kind => panic!("unexpected {:?}", kind),
+        }
+
+        match &definition.types[1].kind {
+            TypeKind::Alias(ty) => {
+                assert_eq!(ty.name, "anyType");
+                assert_eq!(namespace(&namespaces, ty), XSD_NAMESPACE);
+            }
+            kind => panic!("unexpected {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn parse_from_a_reader() {
+        let wsdl = definitions(
+            r#"<message name="PingRequest">
+                <part name="value" element="tns:Ping"/>
+            </message>"#,
+        );
+
+        let (definition, _) = crate::parse_reader(
+            std::io::Cursor::new(wsdl.as_bytes()),
+            Url::parse("http://example.com/").unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(definition.messages[0].name.name, "PingRequest");
+        assert_eq!(definition.messages[0].parts[0].name.name, "value");
+    }
+
+    #[test]
+    fn identical_types_are_merged() {
+        let documented = r#"<xsd:complexType name="Point">
+            <xsd:annotation><xsd:documentation>A point on the plane</xsd:documentation></xsd:annotation>
+            <xsd:sequence>
+                <xsd:element name="x" type="xsd:int">
+                    <xsd:annotation><xsd:documentation>Distance across</xsd:documentation></xsd:annotation>
+                </xsd:element>
+                <xsd:element name="y" type="xsd:int"/>
+            </xsd:sequence>
+        </xsd:complexType>"#;
+
+        let plain = r#"<xsd:complexType name="Point">
+            <xsd:sequence>
+                <xsd:element name="x" type="xsd:int"/>
+                <xsd:element name="y" type="xsd:int"/>
+            </xsd:sequence>
+        </xsd:complexType>"#;
+
+        let (definition, _) = try_parse(&point_schemas(documented, plain)).unwrap();
+        let points = definition.types.iter().filter(|ty| ty.name.name == "Point").count();
+
+        assert_eq!(points, 1);
+    }
+
+    #[test]
+    fn conflicting_types_are_rejected() {
+        let int = r#"<xsd:complexType name="Point">
+            <xsd:sequence><xsd:element name="x" type="xsd:int"/></xsd:sequence>
+        </xsd:complexType>"#;
+
+        let string = r#"<xsd:complexType name="Point">
+            <xsd:sequence><xsd:element name="x" type="xsd:string"/></xsd:sequence>
+        </xsd:complexType>"#;
+
+        let result = try_parse(&point_schemas(int, string));
+
+        assert!(matches!(result, Err(crate::error::Error::ConflictingType(name)) if name == "Point"));
+    }
+
+    #[test]
+    fn nested_imports_resolve_against_their_document() {
+        let (definition, _) = try_parse_with_files(
+            "nested",
+            &[
+                (
+                    "sub/a.xsd",
+                    r#"<xsd:schema xmlns:xsd="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:a">
+                        <xsd:import namespace="urn:b" schemaLocation="b.xsd"/>
+                        <xsd:simpleType name="First">
+                            <xsd:restriction base="xsd:string"></xsd:restriction>
+                        </xsd:simpleType>
+                    </xsd:schema>"#,
+                ),
+                (
+                    "sub/b.xsd",
+                    r#"<xsd:schema xmlns:xsd="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:b">
+                        <xsd:simpleType name="Second">
+                            <xsd:restriction base="xsd:string"></xsd:restriction>
+                        </xsd:simpleType>
+                    </xsd:schema>"#,
+                ),
+            ],
+            r#"<types>
+                <xsd:schema targetNamespace="http://example.com/test">
+                    <xsd:import namespace="urn:a" schemaLocation="sub/a.xsd"/>
+                </xsd:schema>
+            </types>"#,
+        )
+        .unwrap();
+
+        let names = definition.types.iter().map(|ty| ty.name.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["Second", "First"]);
+    }
+
+    #[test]
+    fn operations_resolve_their_messages() {
+        let (definition, _) = try_parse(
+            r#"<message name="AddRequest"><part name="parameters" element="tns:Add"/></message>
+            <message name="AddResponse"><part name="parameters" element="tns:AddResponse"/></message>
+            <message name="ResetRequest"><part name="parameters" element="tns:Reset"/></message>
+            <portType name="MathPortType">
+                <operation name="Add">
+                    <input message="tns:AddRequest"/>
+                    <output message="tns:AddResponse"/>
+                </operation>
+            </portType>
+            <portType name="ControlPortType">
+                <operation name="Reset">
+                    <input message="tns:ResetRequest"/>
+                </operation>
+            </portType>"#,
+        )
+        .unwrap();
+
+        let operations = definition
+            .operations()
+            .map(|(port_type, operation)| {
+                (
+                    port_type.name.name.as_str(),
+                    operation.name.name.as_str(),
+                    definition.input_message(operation).map(|message| message.name.name.as_str()),
+                    definition.output_message(operation).map(|message| message.name.name.as_str()),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            operations,
+            [
+                ("MathPortType", "Add", Some("AddRequest"), Some("AddResponse")),
+                ("ControlPortType", "Reset", Some("ResetRequest"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn documentation_is_joined_around_comments() {
+        let (definition, _) = try_parse(
+            r#"<types>
+                <xsd:schema targetNamespace="http://example.com/test">
+                    <xsd:simpleType name="Code">
+                        <xsd:annotation>
+                            <xsd:documentation>A three <!-- letter --> letter<?pi ignored?> station <![CDATA[<code>]]></xsd:documentation>
+                        </xsd:annotation>
+                        <xsd:restriction base="xsd:string"></xsd:restriction>
+                    </xsd:simpleType>
+                </xsd:schema>
+            </types>"#,
+        )
+        .unwrap();
+
+        assert_eq!(definition.types[0].documentation.as_deref(), Some("A three  letter station <code>"));
+    }
+
+    #[test]
+    fn documentation_is_accepted_anywhere() {
+        let (definition, _) = parse_strict(
+            r#"<documentation>A test service</documentation>
+            <types>
+                <xsd:schema targetNamespace="http://example.com/test">
+                    <xsd:annotation><xsd:documentation>Shared types</xsd:documentation></xsd:annotation>
+                    <xsd:complexType name="Point">
+                        <xsd:annotation><xsd:documentation>A point on the plane</xsd:documentation></xsd:annotation>
+                        <xsd:sequence>
+                            <xsd:element name="label"/>
+                        </xsd:sequence>
+                    </xsd:complexType>
+                </xsd:schema>
+            </types>
+            <message name="PingRequest">
+                <documentation>Sent to check the service is up</documentation>
+                <part name="value" element="tns:Ping"/>
+            </message>"#,
+        )
+        .unwrap();
+
+        assert_eq!(definition.types[0].documentation.as_deref(), Some("A point on the plane"));
+
+        match &definition.types[0].kind {
+            TypeKind::Struct(fields) => {
+                assert!(matches!(&fields[0].ty, FieldKind::Type(ty) if ty.name == "anyType"))
+            }
+            kind => panic!("Unexpected type kind {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn strict_mode_rejects_unsupported_elements() {
+        let result = parse_strict(
+            r#"<message name="PingRequest">
+                <extension/>
+            </message>"#,
+        );
+
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::UnsupportedElement(element, context)) if element == "extension" && context == "message"
+        ));
+    }
+
+    #[test]
+    fn unsupported_elements_are_collected() {
+        let wsdl = definitions(
+            r#"<message name="PingRequest">
+                <extension/>
+                <part name="value" element="tns:Ping"/>
+            </message>"#,
+        );
+
+        let (definition, _, unsupported) = in_directory("unsupported", &[("service.wsdl", &wsdl)], |base| {
+            super::parse_with_options(
+                base.join("service.wsdl").unwrap(),
+                &DefaultResolver,
+                &mut DefaultVisitor,
+                ParseOptions::default(),
+            )
+        })
+        .unwrap();
+
+        assert_eq!(definition.messages[0].parts.len(), 1);
+        assert_eq!(
+            unsupported,
+            [Unsupported {
+                element: "extension".to_owned(),
+                context: "message".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn message_part_with_type() {
+        let (definition, namespaces) = try_parse(
+            r#"<message name="PingRequest">
+                <part name="value" type="xsd:string"/>
+                <part name="body" element="tns:Ping"/>
+            </message>"#,
+        )
+        .unwrap();
+
+        let parts = &definition.messages[0].parts;
+
+        assert_eq!(parts[0].name.name, "value");
+        assert_eq!(namespace(&namespaces, &parts[0].name), "");
+
+        match &parts[0].ty {
+            FieldKind::Type(ty) => {
+                assert_eq!(ty.name, "string");
+                assert_eq!(namespace(&namespaces, ty), "http://www.w3.org/2001/XMLSchema");
+            }
+            ty => panic!("Unexpected part type {:?}", ty),
+        }
+
+        assert_eq!(parts[1].name.name, "body");
+        assert_eq!(namespace(&namespaces, &parts[1].name), "http://example.com/test");
+        assert!(matches!(&parts[1].ty, FieldKind::Type(ty) if ty.name == "Ping"));
+    }
 }
@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+use url::Url;
+
+pub trait Resolver {
+    fn resolve(&self, url: &Url) -> Url;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultResolver;
+
+#[derive(Debug, Clone)]
+pub struct DirectoryResolver {
+    root: PathBuf,
+}
+
+impl Resolver for DefaultResolver {
+    fn resolve(&self, url: &Url) -> Url {
+        url.clone()
+    }
+}
+
+impl DirectoryResolver {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl Resolver for DirectoryResolver {
+    fn resolve(&self, url: &Url) -> Url {
+        match (url.scheme(), url.host_str(), url.path_segments()) {
+            ("http" | "https", Some(host), Some(segments)) => {
+                let mut path = self.root.join(host);
+                path.extend(segments.filter(|segment| !segment.is_empty()));
+
+                Url::from_file_path(&path).unwrap_or_else(|()| url.clone())
+            }
+
+            _ => url.clone(),
+        }
+    }
+}
@@ -7,29 +7,48 @@ pub struct NamespacedName {
     pub name: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum TypeKind {
-    Simple(NamespacedName),
+    Simple(NamespacedName, Facets),
     Struct(Vec<Field>),
     Alias(NamespacedName),
+    Enum(Vec<String>),
+    Choice(Vec<Field>),
+    List(NamespacedName),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Facets {
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub min_inclusive: Option<String>,
+    pub max_inclusive: Option<String>,
+    pub patterns: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Type {
     pub name: NamespacedName,
     pub kind: TypeKind,
+    pub documentation: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum FieldKind {
     Type(NamespacedName),
     Inner(TypeKind),
+    Optional(Box<FieldKind>),
+    List(Box<FieldKind>),
+    Nillable(Box<FieldKind>),
+    Attribute(NamespacedName),
+    Text(NamespacedName),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Field {
     pub name: NamespacedName,
     pub ty: FieldKind,
+    pub documentation: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -38,12 +57,19 @@ pub struct Message {
     pub parts: Vec<Field>,
 }
 
+#[derive(Debug, Clone)]
+pub struct Fault {
+    pub name: String,
+    pub message: NamespacedName,
+}
+
 #[derive(Debug, Clone)]
 pub struct Operation {
     pub name: NamespacedName,
     pub documentation: Option<String>,
     pub input: Option<NamespacedName>,
     pub output: Option<NamespacedName>,
+    pub faults: Vec<Fault>,
 }
 
 #[derive(Debug, Clone)]
@@ -52,6 +78,14 @@ pub struct PortType {
     pub operations: Vec<Operation>,
 }
 
+#[derive(Debug, Clone)]
+pub struct BindingHeader {
+    pub message: NamespacedName,
+    pub part: String,
+    pub body: Option<String>,
+    pub faults: Vec<BindingHeader>,
+}
+
 #[derive(Debug, Clone)]
 pub struct BindingOperation {
     pub name: NamespacedName,
@@ -59,6 +93,16 @@ pub struct BindingOperation {
     pub style: String,
     pub input: Option<String>,
     pub output: Option<String>,
+    pub namespace: Option<String>,
+    pub input_headers: Vec<BindingHeader>,
+    pub output_headers: Vec<BindingHeader>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SoapVersion {
+    #[default]
+    Soap11,
+    Soap12,
 }
 
 #[derive(Debug, Clone)]
@@ -66,6 +110,7 @@ pub struct Binding {
     pub name: NamespacedName,
     pub ty: NamespacedName,
     pub transport: String,
+    pub version: SoapVersion,
     pub operations: Vec<BindingOperation>,
 }
 
@@ -82,9 +127,27 @@ pub struct Service {
     pub ports: Vec<Port>,
 }
 
+#[derive(Debug, Clone)]
+pub struct SubstitutionGroup {
+    pub head: NamespacedName,
+    pub members: Vec<NamespacedName>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unsupported {
+    pub element: String,
+    pub context: String,
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+pub struct ParseOptions {
+    pub strict: bool,
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct Definition {
     pub types: Vec<Type>,
+    pub substitution_groups: Vec<SubstitutionGroup>,
     pub messages: Vec<Message>,
     pub port_types: Vec<PortType>,
     pub bindings: Vec<Binding>,
@@ -111,6 +174,12 @@ impl Namespaces {
     }
 }
 
+impl Facets {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 impl NamespacedName {
     pub fn new(namespaces: &mut Namespaces, namespace: &str, name: String) -> Self {
         Self {
@@ -123,3 +192,23 @@ impl NamespacedName {
         self.namespace_idx
     }
 }
+
+impl Definition {
+    pub fn operations(&self) -> impl Iterator<Item = (&PortType, &Operation)> {
+        self.port_types
+            .iter()
+            .flat_map(|port_type| port_type.operations.iter().map(move |operation| (port_type, operation)))
+    }
+
+    pub fn message(&self, name: &NamespacedName) -> Option<&Message> {
+        self.messages.iter().find(|message| &message.name == name)
+    }
+
+    pub fn input_message(&self, operation: &Operation) -> Option<&Message> {
+        operation.input.as_ref().and_then(|input| self.message(input))
+    }
+
+    pub fn output_message(&self, operation: &Operation) -> Option<&Message> {
+        operation.output.as_ref().and_then(|output| self.message(output))
+    }
+}
@@ -0,0 +1,16 @@
+use super::types::{Message, Operation, Service, Type};
+
+pub trait Visitor {
+    fn visit_type(&mut self, _ty: &Type) {}
+
+    fn visit_message(&mut self, _message: &Message) {}
+
+    fn visit_operation(&mut self, _operation: &Operation) {}
+
+    fn visit_service(&mut self, _service: &Service) {}
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultVisitor;
+
+impl Visitor for DefaultVisitor {}